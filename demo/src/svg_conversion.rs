@@ -1,9 +1,10 @@
-use freedraw::{get_stroke, get_svg_path_from_stroke, InputPoint, StrokeOptions, TaperOptions, TaperType};
+use freedraw::{get_stroke, InputPoint, StrokeOptions, SvgDocument, SvgStroke, TaperOptions, TaperType};
 use std::fs::{self, File};
 use std::io::{Read, Write};
 use std::path::Path;
 use serde_json::Value;
 
+#[allow(clippy::expect_fun_call)]
 fn load_test_data(filename: &str) -> serde_json::Value {
     let mut file = File::open(format!("../tests/{}", filename)).expect(&format!("Could not open {}", filename));
     let mut contents = String::new();
@@ -11,50 +12,6 @@ fn load_test_data(filename: &str) -> serde_json::Value {
     serde_json::from_str(&contents).expect("Could not parse JSON")
 }
 
-// New function to calculate viewBox based on path data
-fn calculate_viewbox(points: &[InputPoint]) -> String {
-    if points.is_empty() {
-        return "0 0 100 100".to_string(); // Default viewBox
-    }
-    
-    // Initialize min/max values
-    let mut min_x = f64::MAX;
-    let mut min_y = f64::MAX;
-    let mut max_x = f64::MIN;
-    let mut max_y = f64::MIN;
-    
-    // Find the bounds of all points
-    for point in points {
-        let (x, y) = match point {
-            InputPoint::Array([x, y], _) => (*x, *y),
-            InputPoint::Struct { x, y, .. } => (*x, *y),
-        };
-        
-        min_x = min_x.min(x);
-        min_y = min_y.min(y);
-        max_x = max_x.max(x);
-        max_y = max_y.max(y);
-    }
-    
-    // Add padding (20% of the size)
-    let width = max_x - min_x;
-    let height = max_y - min_y;
-    let padding_x = width * 0.2;
-    let padding_y = height * 0.2;
-    
-    // Ensure minimum size for very small drawings
-    let width = width.max(10.0) + padding_x * 2.0;
-    let height = height.max(10.0) + padding_y * 2.0;
-    
-    // Return the viewBox string
-    format!("{} {} {} {}", 
-        min_x - padding_x, 
-        min_y - padding_y, 
-        width, 
-        height
-    )
-}
-
 fn convert_json_to_input_points(points_json: &[Value]) -> Vec<InputPoint> {
     points_json
         .iter()
@@ -84,45 +41,46 @@ fn convert_json_to_input_points(points_json: &[Value]) -> Vec<InputPoint> {
                     x: obj["x"].as_f64().unwrap(),
                     y: obj["y"].as_f64().unwrap(),
                     pressure: obj.get("pressure").and_then(|p| p.as_f64()),
+                    timestamp: None,
+                    orientation: None,
                 }
             }
         })
         .collect()
 }
 
-fn generate_svg_file(path_data: &str, filename: &str, color: &str, input_points: &[InputPoint]) {
-    // Create the dist directory if it doesn't exist
-    let dist_dir = Path::new("examples/svg");
-    if !dist_dir.exists() {
-        fs::create_dir_all(dist_dir).expect("Failed to create dist directory");
-    }
+fn generate_svg_file(outline: &[[f64; 2]], filename: &str, color: &str) {
+    let mut document = SvgDocument {
+        padding: Some(10.0),
+        preserve_aspect_ratio: Some("xMidYMid meet".to_string()),
+        ..SvgDocument::new()
+    };
+    document.add_stroke(SvgStroke {
+        fill: Some(color.to_string()),
+        ..SvgStroke::new(outline.to_vec())
+    });
 
-    let file_path = dist_dir.join(filename);
-    
-    // Calculate viewBox based on input points
-    let viewbox = calculate_viewbox(input_points);
-    
-    let svg_content = format!(
-        r#"<?xml version="1.0" encoding="UTF-8"?>
-<svg xmlns="http://www.w3.org/2000/svg" viewBox="{}" preserveAspectRatio="xMidYMid meet">
-  <path d="{}" 
-        fill="{}" 
-        stroke="none" 
-        stroke-width="0"
-        stroke-linejoin="round" 
-        stroke-linecap="round" />
-</svg>"#,
-        viewbox, path_data, color
-    );
-    
-    let mut file = File::create(file_path).expect("Failed to create SVG file");
-    file.write_all(svg_content.as_bytes()).expect("Failed to write SVG content");
-    
-    println!("Generated SVG file: examples/svg/{}", filename);
+    write_svg_file(&document, filename);
 }
 
 // Also create a function to generate both fill and stroke versions for comparison
-fn generate_svg_file_with_stroke(path_data: &str, filename: &str, fill_color: &str, stroke_color: &str, stroke_width: f64, input_points: &[InputPoint]) {
+fn generate_svg_file_with_stroke(outline: &[[f64; 2]], filename: &str, fill_color: &str, stroke_color: &str, stroke_width: f64) {
+    let mut document = SvgDocument {
+        padding: Some(10.0),
+        preserve_aspect_ratio: Some("xMidYMid meet".to_string()),
+        ..SvgDocument::new()
+    };
+    document.add_stroke(SvgStroke {
+        fill: Some(fill_color.to_string()),
+        stroke: Some(stroke_color.to_string()),
+        stroke_width: Some(stroke_width),
+        ..SvgStroke::new(outline.to_vec())
+    });
+
+    write_svg_file(&document, filename);
+}
+
+fn write_svg_file(document: &SvgDocument, filename: &str) {
     // Create the dist directory if it doesn't exist
     let dist_dir = Path::new("examples/svg");
     if !dist_dir.exists() {
@@ -131,24 +89,8 @@ fn generate_svg_file_with_stroke(path_data: &str, filename: &str, fill_color: &s
 
     let file_path = dist_dir.join(filename);
     
-    // Calculate viewBox based on input points
-    let viewbox = calculate_viewbox(input_points);
-    
-    let svg_content = format!(
-        r#"<?xml version="1.0" encoding="UTF-8"?>
-<svg xmlns="http://www.w3.org/2000/svg" viewBox="{}" preserveAspectRatio="xMidYMid meet">
-  <path d="{}" 
-        fill="{}" 
-        stroke="{}" 
-        stroke-width="{}"
-        stroke-linejoin="round" 
-        stroke-linecap="round" />
-</svg>"#,
-        viewbox, path_data, fill_color, stroke_color, stroke_width
-    );
-    
     let mut file = File::create(file_path).expect("Failed to create SVG file");
-    file.write_all(svg_content.as_bytes()).expect("Failed to write SVG content");
+    file.write_all(document.to_svg().as_bytes()).expect("Failed to write SVG content");
     
     println!("Generated SVG file: examples/svg/{}", filename);
 }
@@ -201,18 +143,16 @@ fn process_raw_points(name: &str, points: &[Value], color: &str) {
         };
         
         let stroke = get_stroke(&input_points, &options);
-        let path_data = get_svg_path_from_stroke(&stroke, true);
         
         // Generate both fill and stroke versions
         let filename = format!("{}{}",  name, suffix);
-        generate_svg_file(&path_data, &format!("{}.svg", filename), color, &input_points);
+        generate_svg_file(&stroke, &format!("{}.svg", filename), color);
         generate_svg_file_with_stroke(
-            &path_data, 
+            &stroke, 
             &format!("{}_stroke.svg", filename), 
             "transparent", 
             color, 
-            2.0, 
-            &input_points
+            2.0
         );
         
         // Add tapered versions
@@ -234,17 +174,15 @@ fn process_raw_points(name: &str, points: &[Value], color: &str) {
         };
         
         let stroke = get_stroke(&input_points, &tapered_options);
-        let path_data = get_svg_path_from_stroke(&stroke, true);
         
         let filename = format!("{}{}_tapered", name, suffix);
-        generate_svg_file(&path_data, &format!("{}.svg", filename), color, &input_points);
+        generate_svg_file(&stroke, &format!("{}.svg", filename), color);
         generate_svg_file_with_stroke(
-            &path_data, 
+            &stroke, 
             &format!("{}_stroke.svg", filename), 
             "transparent", 
             color, 
-            2.0, 
-            &input_points
+            2.0
         );
     }
 }
@@ -289,17 +227,15 @@ pub fn main() {
         };
         
         let stroke = get_stroke(&input_points, &options);
-        let path_data = get_svg_path_from_stroke(&stroke, true);
         
         // Generate both fill-only and stroke+fill versions for comparison
-        generate_svg_file(&path_data, &format!("{}.svg", name), color, &input_points);
+        generate_svg_file(&stroke, &format!("{}.svg", name), color);
         generate_svg_file_with_stroke(
-            &path_data, 
+            &stroke, 
             &format!("{}_with_stroke.svg", name), 
             "transparent", 
             color, 
-            2.0, 
-            &input_points
+            2.0
         );
         
         // Tapered options (start and end tapers)
@@ -322,17 +258,15 @@ pub fn main() {
             };
             
             let stroke = get_stroke(&input_points, &tapered_options);
-            let path_data = get_svg_path_from_stroke(&stroke, true);
-            
+                
             // Generate both fill-only and stroke+fill versions for comparison
-            generate_svg_file(&path_data, &format!("{}_tapered.svg", name), color, &input_points);
+            generate_svg_file(&stroke, &format!("{}_tapered.svg", name), color);
             generate_svg_file_with_stroke(
-                &path_data, 
+                &stroke, 
                 &format!("{}_tapered_with_stroke.svg", name), 
                 "transparent", 
                 color, 
-                2.0, 
-                &input_points
+                2.0
             );
         }
     };
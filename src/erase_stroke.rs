@@ -0,0 +1,265 @@
+use crate::types::{CapStyle, Eraser, InputPoint, StrokeOptions, StrokePiece, TaperOptions};
+use crate::vec::{dist, dpr, len2, lrp, mul, nearest_t, per, sub};
+
+/// Erase the parts of a stroke that an eraser passes over, keeping the rest
+/// as new strokes.
+///
+/// The input points are cut where the line between them meets the edge of
+/// the eraser, with the pressure and timestamp at each cut interpolated
+/// between the points on either side, so that each piece's stroke ends at the
+/// edge of the eraser.
+///
+/// # Arguments
+/// * `points` - The stroke's input points
+/// * `options` - The options the stroke is drawn with
+/// * `eraser` - The eraser's path and radius
+///
+/// # Returns
+/// The pieces of the stroke that are left, from its start to its end
+pub fn erase_stroke(
+    points: &[InputPoint],
+    options: &StrokeOptions,
+    eraser: &Eraser,
+) -> Vec<StrokePiece> {
+    let mut pieces = Vec::new();
+
+    // A dot is either erased or it isn't
+    if points.len() == 1 {
+        if kept_intervals(points[0].point(), points[0].point(), eraser).is_empty() {
+            return pieces;
+        }
+        return vec![stroke_piece(points.to_vec(), options, eraser, false, false)];
+    }
+
+    // The points of the piece being built, and whether it starts at a cut
+    let mut current: Option<(Vec<InputPoint>, bool)> = None;
+
+    let mut finish = |current: Option<(Vec<InputPoint>, bool)>, is_end_cut: bool| {
+        let Some((piece, is_start_cut)) = current else {
+            return;
+        };
+
+        // Leave out slivers where the eraser only just misses the line
+        let length: f64 = piece.windows(2).map(|pair| dist(pair[0].point(), pair[1].point())).sum();
+        if (is_start_cut || is_end_cut) && length <= eraser.radius * 1e-6 {
+            return;
+        }
+
+        pieces.push(stroke_piece(piece, options, eraser, is_start_cut, is_end_cut));
+    };
+
+    for (i, pair) in points.windows(2).enumerate() {
+        let (a, b) = (&pair[0], &pair[1]);
+        let kept = kept_intervals(a.point(), b.point(), eraser);
+
+        // The eraser covers the end of the piece so far
+        if kept.first().map_or(true, |&(start, _)| start > 0.0) {
+            finish(current.take(), true);
+        }
+
+        for (start, end) in kept {
+            let (piece, _) = current
+                .get_or_insert_with(|| (vec![interpolate(a, b, start)], i > 0 || start > 0.0));
+            piece.push(interpolate(a, b, end));
+
+            if end < 1.0 {
+                finish(current.take(), true);
+            }
+        }
+    }
+
+    finish(current.take(), false);
+
+    pieces
+}
+
+/// The stroke's options, with the eraser's cut ends in place of the cut
+/// ends' own.
+fn stroke_piece(
+    points: Vec<InputPoint>,
+    options: &StrokeOptions,
+    eraser: &Eraser,
+    is_start_cut: bool,
+    is_end_cut: bool,
+) -> StrokePiece {
+    let cut_ends = eraser.cut_ends.clone().unwrap_or(TaperOptions {
+        cap: Some(true),
+        cap_style: Some(CapStyle::Butt),
+        ..Default::default()
+    });
+
+    let mut options = options.clone();
+    if is_start_cut {
+        options.start = Some(cut_ends.clone());
+    }
+    if is_end_cut {
+        options.end = Some(cut_ends);
+        // Draw the stroke all the way to the cut
+        options.last = Some(true);
+    }
+    if is_start_cut || is_end_cut {
+        options.closed = Some(false);
+    }
+
+    StrokePiece {
+        points,
+        options,
+        is_start_cut,
+        is_end_cut,
+    }
+}
+
+/// A point part of the way from `a` to `b`.
+fn interpolate(a: &InputPoint, b: &InputPoint, t: f64) -> InputPoint {
+    if t <= 0.0 {
+        return a.clone();
+    }
+    if t >= 1.0 {
+        return b.clone();
+    }
+
+    let mix = |a: Option<f64>, b: Option<f64>| match (a, b) {
+        (Some(a), Some(b)) => Some(a + (b - a) * t),
+        // Take the value from the nearer point
+        (a, b) => {
+            if t < 0.5 {
+                a
+            } else {
+                b
+            }
+        }
+    };
+
+    let point = lrp(a.point(), b.point(), t);
+    let pressure = mix(a.pressure(), b.pressure());
+
+    match (a, b) {
+        (InputPoint::Array(..), InputPoint::Array(..)) => InputPoint::Array(point, pressure),
+        _ => InputPoint::Struct {
+            x: point[0],
+            y: point[1],
+            pressure,
+            timestamp: mix(a.timestamp(), b.timestamp()),
+            orientation: if t < 0.5 { a.orientation() } else { b.orientation() },
+        },
+    }
+}
+
+/// The parts of the line from `a` to `b` that the eraser misses, as how far
+/// along the line they start and end, from 0 to 1.
+fn kept_intervals(a: [f64; 2], b: [f64; 2], eraser: &Eraser) -> Vec<(f64, f64)> {
+    let direction = sub(b, a);
+    let radius = eraser.radius;
+
+    let mut erased: Vec<(f64, f64)> = Vec::new();
+    if radius > 0.0 {
+        let path = &eraser.path;
+        let segments = path.windows(2).map(|pair| (pair[0], pair[1]));
+        let dots = path.first().filter(|_| path.len() == 1).map(|&p| (p, p));
+
+        for (e0, e1) in segments.chain(dots) {
+            if len2(direction) == 0.0 {
+                // The line is a single point, which is covered or not
+                if distance_to_segment(a, e0, e1) <= radius {
+                    erased.push((0.0, 1.0));
+                }
+                continue;
+            }
+
+            let interval = [
+                circle_interval(a, direction, e0, radius),
+                circle_interval(a, direction, e1, radius),
+                band_interval(a, direction, e0, e1, radius),
+            ]
+            .into_iter()
+            .flatten()
+            .reduce(|x, y| (x.0.min(y.0), x.1.max(y.1)));
+
+            if let Some((start, end)) = interval {
+                if end >= 0.0 && start <= 1.0 {
+                    erased.push((start.max(0.0), end.min(1.0)));
+                }
+            }
+        }
+    }
+
+    erased.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    // Whatever is between the erased parts is kept
+    let mut kept = Vec::new();
+    let mut t = 0.0;
+    for (start, end) in erased {
+        if start > t {
+            kept.push((t, start));
+        }
+        t = f64::max(t, end);
+    }
+    if t < 1.0 {
+        kept.push((t, 1.0));
+    }
+
+    kept
+}
+
+/// Where the line from `a` along `direction` is within `radius` of a point,
+/// as how far along the line it enters and leaves.
+fn circle_interval(
+    a: [f64; 2],
+    direction: [f64; 2],
+    center: [f64; 2],
+    radius: f64,
+) -> Option<(f64, f64)> {
+    let offset = sub(a, center);
+    let qa = len2(direction);
+    let qb = 2.0 * dpr(direction, offset);
+    let qc = len2(offset) - radius * radius;
+
+    let discriminant = qb * qb - 4.0 * qa * qc;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let root = discriminant.sqrt();
+    Some(((-qb - root) / (2.0 * qa), (-qb + root) / (2.0 * qa)))
+}
+
+/// Where the line from `a` along `direction` is within the band that runs
+/// from `e0` to `e1` and reaches `radius` to either side.
+fn band_interval(
+    a: [f64; 2],
+    direction: [f64; 2],
+    e0: [f64; 2],
+    e1: [f64; 2],
+    radius: f64,
+) -> Option<(f64, f64)> {
+    let length = dist(e0, e1);
+    if length == 0.0 {
+        return None;
+    }
+
+    let along = mul(sub(e1, e0), 1.0 / length);
+    let across = per(along);
+    let offset = sub(a, e0);
+
+    let (t0, t1) = slab(dpr(offset, along), dpr(direction, along), 0.0, length)?;
+    let (u0, u1) = slab(dpr(offset, across), dpr(direction, across), -radius, radius)?;
+
+    let (start, end) = (t0.max(u0), t1.min(u1));
+    (start <= end).then_some((start, end))
+}
+
+/// Where `x + t * dx` is between `low` and `high`, as a range of `t`.
+fn slab(x: f64, dx: f64, low: f64, high: f64) -> Option<(f64, f64)> {
+    if dx == 0.0 {
+        return (low..=high)
+            .contains(&x)
+            .then_some((f64::NEG_INFINITY, f64::INFINITY));
+    }
+
+    let (t0, t1) = ((low - x) / dx, (high - x) / dx);
+    Some((t0.min(t1), t0.max(t1)))
+}
+
+fn distance_to_segment(point: [f64; 2], a: [f64; 2], b: [f64; 2]) -> f64 {
+    dist(point, lrp(a, b, nearest_t(point, a, b)))
+}
@@ -0,0 +1,81 @@
+use crate::types::{HookRemoval, InputPoint};
+use crate::vec::{cpr, dist, dpr, sub};
+use std::f64::consts::PI;
+
+/// Find the hook a stylus can leave at the end of a stroke as it lifts: a
+/// short flick in a new direction, drawn as the pressure falls away.
+///
+/// The hook starts where the line turns sharply, no further than
+/// `max_length` from the end, and where the pressure falls by at least
+/// `min_pressure_drop` from there to the end. The line before the turn must
+/// be at least as long as the hook, so that short marks such as ticks are
+/// left alone. Where more than one turn would do, the sharpest is used.
+///
+/// # Arguments
+/// * `points` - The stroke's input points
+/// * `hook_removal` - The thresholds for finding a hook
+///
+/// # Returns
+/// The index of the first point of the hook, so that the stroke without it
+/// is `&points[..index]`, or `None` if there's no hook
+pub fn find_end_hook(points: &[InputPoint], hook_removal: &HookRemoval) -> Option<usize> {
+    let max_length = hook_removal.max_length.unwrap_or(10.0);
+    let min_angle = hook_removal.min_angle.unwrap_or(PI / 3.0);
+    let min_pressure_drop = hook_removal.min_pressure_drop.unwrap_or(0.1);
+
+    if points.len() < 3 {
+        return None;
+    }
+
+    let end = points[points.len() - 1].point();
+    let end_pressure = points[points.len() - 1].pressure().unwrap_or(0.5);
+
+    // The sharpest turn found so far, and its angle
+    let mut hook: Option<(usize, f64)> = None;
+    let mut hook_length = 0.0;
+
+    // Walk back from the end, trying each point as the turn
+    for i in (1..points.len() - 1).rev() {
+        let corner = points[i].point();
+        hook_length += dist(corner, points[i + 1].point());
+        if hook_length > max_length {
+            break;
+        }
+
+        // The pressure has to fall along the hook
+        let pressure_drop = points[i].pressure().unwrap_or(0.5) - end_pressure;
+        if pressure_drop < min_pressure_drop {
+            continue;
+        }
+
+        // The direction the line came from, over at least the hook's length
+        let Some(before) = point_before(points, i, hook_length) else {
+            continue;
+        };
+
+        let incoming = sub(corner, before);
+        let outgoing = sub(end, corner);
+        let angle = cpr(incoming, outgoing).abs().atan2(dpr(incoming, outgoing));
+
+        if angle >= min_angle && hook.map_or(true, |(_, sharpest)| angle > sharpest) {
+            hook = Some((i, angle));
+        }
+    }
+
+    hook.map(|(i, _)| i + 1)
+}
+
+/// The first point at least `length` back along the line from the point at
+/// `index`, or `None` if the line isn't that long.
+fn point_before(points: &[InputPoint], index: usize, length: f64) -> Option<[f64; 2]> {
+    let mut travelled = 0.0;
+
+    for i in (0..index).rev() {
+        travelled += dist(points[i].point(), points[i + 1].point());
+        if travelled >= length {
+            return Some(points[i].point());
+        }
+    }
+
+    None
+}
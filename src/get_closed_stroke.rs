@@ -0,0 +1,27 @@
+use crate::get_stroke::get_stroke;
+use crate::get_stroke_outline_points::get_closed_outline_points;
+use crate::get_stroke_points::get_closed_stroke_points;
+use crate::types::{ClosedStroke, InputPoint, StrokeOptions};
+
+/// Get the outline of a stroke that loops back on itself, such as a lasso or
+/// a circle. The line runs from the last point back to the first without caps,
+/// and is smoothed across the seam like anywhere else along it.
+///
+/// # Arguments
+/// * `points` - An array of points (with optional pressure data)
+/// * `options` - Options for the stroke generation. The caps, tapers and
+///   `last` options don't apply to a loop.
+///
+/// # Returns
+/// The outer and inner rings of the loop. With fewer than three distinct
+/// points there is no loop, so the outer ring is the outline from `get_stroke`
+/// and the inner ring is empty.
+pub fn get_closed_stroke(points: &[InputPoint], options: &StrokeOptions) -> ClosedStroke {
+    match get_closed_stroke_points(points, options) {
+        Some(stroke_points) => get_closed_outline_points(&stroke_points, options),
+        None => ClosedStroke {
+            outer: get_stroke(points, options),
+            inner: Vec::new(),
+        },
+    }
+}
@@ -0,0 +1,145 @@
+use crate::get_stroke_outline_points::get_stroke_outline;
+use crate::types::{MeshVertex, StrokeMesh, StrokeOptions, StrokePoint};
+use crate::vec::{dist, dpr, sub, uni};
+
+/// Get a triangle mesh that covers a stroke, for drawing on the GPU.
+///
+/// The two sides of the outline are joined into a strip of triangles,
+/// and each cap is split into a fan around the end of the line. Strokes
+/// that cross over themselves give overlapping triangles instead of a
+/// polygon that has to be triangulated. So does the original start cap,
+/// which turns back over the line, unless the start has a `cap_style`.
+///
+/// # Arguments
+/// * `points` - An array of StrokePoints as returned from `get_stroke_points`
+/// * `options` - Options for the stroke generation
+///
+/// # Returns
+/// The vertices of the mesh and the indices of its triangles, three per triangle
+pub fn get_stroke_mesh(points: &[StrokePoint], options: &StrokeOptions) -> StrokeMesh {
+    let outline = get_stroke_outline(points, options);
+    let mut mesh = StrokeMesh::default();
+
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return mesh;
+    };
+
+    let total_length = last.running_length;
+    let distance = |index: usize| {
+        if total_length > 0.0 {
+            points[index].running_length / total_length
+        } else {
+            0.0
+        }
+    };
+
+    // The sides, with the left edge across at -1 and the right edge at 1
+    let left: Vec<u32> = outline
+        .left
+        .iter()
+        .zip(&outline.left_indices)
+        .map(|(&position, &index)| mesh.add_vertex(position, distance(index), -1.0))
+        .collect();
+    let right: Vec<u32> = outline
+        .right
+        .iter()
+        .zip(&outline.right_indices)
+        .map(|(&position, &index)| mesh.add_vertex(position, distance(index), 1.0))
+        .collect();
+
+    // Zip the sides together, moving along whichever side is behind
+    let progress = |indices: &[usize], i: usize| {
+        (indices[i], i as f64 / indices.len() as f64)
+    };
+
+    let (mut i, mut j) = (0, 0);
+    while i + 1 < left.len() || j + 1 < right.len() {
+        let is_left_next = j + 1 >= right.len()
+            || (i + 1 < left.len()
+                && progress(&outline.left_indices, i + 1)
+                    <= progress(&outline.right_indices, j + 1));
+
+        if is_left_next {
+            mesh.indices.extend([left[i], right[j], left[i + 1]]);
+            i += 1;
+        } else {
+            mesh.indices.extend([left[i], right[j], right[j + 1]]);
+            j += 1;
+        }
+    }
+
+    // The start cap runs from the left side to the right side, and the
+    // end cap from the right side back to the left side
+    let start_cap = mesh.add_cap(&outline.start_cap, first.point, 0.0, false);
+    let end_cap = mesh.add_cap(&outline.end_cap, last.point, 1.0, true);
+
+    match (left.first(), right.first(), left.last(), right.last()) {
+        (Some(&l0), Some(&r0), Some(&ln), Some(&rn)) => {
+            if !start_cap.is_empty() {
+                mesh.add_fan(first.point, 0.0, &[&[l0], &start_cap[..], &[r0]].concat());
+            }
+            if !end_cap.is_empty() {
+                mesh.add_fan(last.point, 1.0, &[&[rn], &end_cap[..], &[ln]].concat());
+            }
+        }
+        // Without any sides, the caps meet each other
+        _ => mesh.add_fan(first.point, 0.0, &[start_cap, end_cap].concat()),
+    }
+
+    mesh
+}
+
+impl StrokeMesh {
+    fn add_vertex(&mut self, position: [f64; 2], distance: f64, across: f64) -> u32 {
+        self.vertices.push(MeshVertex {
+            position,
+            distance,
+            across,
+        });
+        (self.vertices.len() - 1) as u32
+    }
+
+    /// Add the vertices of a cap. Across the cap, each vertex is measured
+    /// from `center` toward the sides at either end of the cap.
+    fn add_cap(
+        &mut self,
+        cap: &[[f64; 2]],
+        center: [f64; 2],
+        distance: f64,
+        is_end: bool,
+    ) -> Vec<u32> {
+        let (left, right) = match (cap.first(), cap.last()) {
+            (Some(&first), Some(&last)) if is_end => (last, first),
+            (Some(&first), Some(&last)) => (first, last),
+            _ => return Vec::new(),
+        };
+
+        let half_width = dist(left, right) / 2.0;
+        let direction = uni(sub(right, left));
+
+        cap.iter()
+            .map(|&position| {
+                let across = if half_width > 0.0 {
+                    (dpr(sub(position, center), direction) / half_width).clamp(-1.0, 1.0)
+                } else {
+                    0.0
+                };
+                self.add_vertex(position, distance, across)
+            })
+            .collect()
+    }
+
+    /// Add a vertex at `center` and a fan of triangles around it, one for
+    /// each pair of neighbouring vertices in `ring`.
+    fn add_fan(&mut self, center: [f64; 2], distance: f64, ring: &[u32]) {
+        if ring.len() < 2 {
+            return;
+        }
+
+        let center = self.add_vertex(center, distance, 0.0);
+        for (i, &vertex) in ring.iter().enumerate() {
+            let next = ring[(i + 1) % ring.len()];
+            self.indices.extend([center, vertex, next]);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::get_stroke_outline_points::{get_stroke_outline_points, get_tapered_stroke_radii};
+use crate::polygon::{area_moment, convex_hull, resolve_overlaps, signed_area};
+use crate::types::{Bounds, OrientedBounds, StrokeMetrics, StrokeOptions, StrokePoint};
+use crate::vec::{add, div, dpr, mul, per, sub, uni};
+
+/// Measure a stroke: its bounds, length, area and radius.
+///
+/// The measurements are of the stroke as it's drawn by
+/// `get_stroke_outline_points` with the same options, so they take its
+/// pressure, thinning and tapers into account.
+///
+/// # Arguments
+/// * `points` - The stroke points, as returned by `get_stroke_points`
+/// * `options` - The options the stroke is drawn with
+///
+/// # Returns
+/// The stroke's measurements, or `None` if it has no outline
+pub fn get_stroke_metrics(
+    points: &[StrokePoint],
+    options: &StrokeOptions,
+) -> Option<StrokeMetrics> {
+    let outline = get_stroke_outline_points(points, options);
+    let bounds = Bounds::from_points(&outline)?;
+
+    // Where the outline overlaps itself, the overlap is only filled once
+    let mut area = 0.0;
+    let mut moment = [0.0, 0.0];
+    for polygon in resolve_overlaps(&outline) {
+        for ring in std::iter::once(&polygon.outer).chain(&polygon.holes) {
+            area += signed_area(ring);
+            moment = add(moment, area_moment(ring));
+        }
+    }
+    let centroid = if area > 0.0 {
+        div(moment, area)
+    } else {
+        [(bounds.min[0] + bounds.max[0]) / 2.0, (bounds.min[1] + bounds.max[1]) / 2.0]
+    };
+
+    let radii = get_tapered_stroke_radii(points, options);
+    // The running length also counts the points skipped at the start of the
+    // stroke, so add up the distances between the points that are left
+    let length: f64 = points.iter().skip(1).map(|point| point.distance).sum();
+    let average_radius = if length > 0.0 {
+        // Weight each radius by the length of the stroke around it
+        let total: f64 = radii
+            .windows(2)
+            .zip(points.iter().skip(1))
+            .map(|(r, point)| (r[0] + r[1]) / 2.0 * point.distance)
+            .sum();
+        total / length
+    } else {
+        radii.iter().sum::<f64>() / radii.len() as f64
+    };
+
+    Some(StrokeMetrics {
+        bounds,
+        oriented_bounds: get_oriented_bounds(&outline)?,
+        length,
+        area,
+        centroid,
+        min_radius: radii.iter().copied().fold(f64::INFINITY, f64::min),
+        max_radius: radii.iter().copied().fold(0.0, f64::max),
+        average_radius,
+    })
+}
+
+/// The smallest turned rectangle that contains some points, which lies along
+/// one of the edges of their convex hull.
+fn get_oriented_bounds(points: &[[f64; 2]]) -> Option<OrientedBounds> {
+    let hull = convex_hull(points);
+    let &first = hull.first()?;
+
+    let axes: Vec<[f64; 2]> = if hull.len() == 1 {
+        vec![[1.0, 0.0]]
+    } else {
+        (0..hull.len())
+            .map(|i| uni(sub(hull[(i + 1) % hull.len()], hull[i])))
+            .collect()
+    };
+
+    axes.into_iter()
+        .map(|axis| {
+            // Measure from the first point to keep the numbers small
+            let normal = per(axis);
+            let mut u = [f64::INFINITY, f64::NEG_INFINITY];
+            let mut v = u;
+            for &point in &hull {
+                let offset = sub(point, first);
+                let (du, dv) = (dpr(offset, axis), dpr(offset, normal));
+                u = [u[0].min(du), u[1].max(du)];
+                v = [v[0].min(dv), v[1].max(dv)];
+            }
+
+            let center = add(
+                first,
+                add(mul(axis, (u[0] + u[1]) / 2.0), mul(normal, (v[0] + v[1]) / 2.0)),
+            );
+            OrientedBounds {
+                center,
+                size: [u[1] - u[0], v[1] - v[0]],
+                angle: axis[1].atan2(axis[0]),
+            }
+        })
+        .min_by(|a, b| (a.size[0] * a.size[1]).total_cmp(&(b.size[0] * b.size[1])))
+}
@@ -1,11 +1,22 @@
 use crate::get_stroke_radius::get_stroke_radius;
-use crate::types::{StrokeOptions, StrokePoint};
-use crate::vec::{add, dist2, dpr, mul, neg, per, rot_around};
+use crate::polygon::signed_area;
+use crate::types::{
+    CapStyle, ClosedStroke, JoinStyle, Nib, PenOrientation, PressureSimulation, StrokeOptions,
+    StrokeOutline, StrokePoint, TaperType,
+};
+use crate::vec::{add, cpr, dist, dist2, dpr, len, mul, neg, per, rot_around, sub, uni};
 use std::f64::consts::PI;
 
 // This is the rate of change for simulated pressure. It could be an option.
 const RATE_OF_PRESSURE_CHANGE: f64 = 0.275;
 
+// The time between samples (in milliseconds) that the distance-based pressure
+// simulation was tuned for, used when simulating pressure from velocity
+pub(crate) const FRAME_DURATION: f64 = 1000.0 / 60.0;
+
+// The largest angle between the points of a round join
+const JOIN_ARC_STEP: f64 = PI / 12.0;
+
 // Browser strokes seem to be off if PI is regular, a tiny offset seems to fix it
 const FIXED_PI: f64 = PI + 0.0001;
 
@@ -21,300 +32,894 @@ pub fn get_stroke_outline_points(
     points: &[StrokePoint],
     options: &StrokeOptions,
 ) -> Vec<[f64; 2]> {
-    let size = options.size.unwrap_or(16.0);
-    let smoothing = options.smoothing.unwrap_or(0.5);
-    let thinning = options.thinning.unwrap_or(0.5);
-    let simulate_pressure = options.simulate_pressure.unwrap_or(true);
-    let _is_complete = options.last.unwrap_or(false);
-
-    // Define the easing function or use the default (identity function)
-    let easing_fn = options.easing.unwrap_or(|t| t);
+    let params = OutlineParams::new(points, options);
+    let outline = get_stroke_outline(points, options);
+
+    assemble_outline(
+        &params,
+        &outline.start_cap,
+        &outline.right,
+        &outline.end_cap,
+        &outline.left,
+    )
+}
+
+/// Get the parts of a stroke's outline separately, before they're joined
+/// into a polygon by `get_stroke_outline_points`.
+///
+/// # Arguments
+/// * `points` - An array of StrokePoints as returned from `get_stroke_points`
+/// * `options` - Options for the stroke generation
+///
+/// # Returns
+/// The left and right sides from the start of the stroke to its end, along
+/// with the index of the stroke point each side point came from, and the caps
+pub fn get_stroke_outline(points: &[StrokePoint], options: &StrokeOptions) -> StrokeOutline {
+    outline_with_params(points, &OutlineParams::new(points, options))
+}
+
+/// Get the outline of stroke points whose `pressure` is already the radius
+/// of the stroke at each point, before tapering.
+pub(crate) fn get_outline_points_from_radii(
+    points: &[StrokePoint],
+    options: &StrokeOptions,
+) -> Vec<[f64; 2]> {
+    let mut params = OutlineParams::new(points, options);
+    params.pressure_is_radius = true;
+    params.simulate_pressure = false;
+
+    let outline = outline_with_params(points, &params);
+    assemble_outline(
+        &params,
+        &outline.start_cap,
+        &outline.right,
+        &outline.end_cap,
+        &outline.left,
+    )
+}
+
+/// The radius of the stroke at each of its points, before tapering, with the
+/// pressure simulated as it is for the outline.
+pub(crate) fn get_stroke_radii(points: &[StrokePoint], options: &StrokeOptions) -> Vec<f64> {
+    let params = OutlineParams::new(points, options);
+    let Some(first) = points.first() else {
+        return Vec::new();
+    };
 
-    // Get start and end options with defaults
-    let start_options = options.start.clone().unwrap_or_default();
-    let end_options = options.end.clone().unwrap_or_default();
+    // The first point isn't offset, so it keeps the pressure its cap is drawn with
+    let mut cursor = OutlineCursor::new(points, &params);
+    std::iter::once(params.radius(first.pressure))
+        .chain(points[1..].iter().map(|curr| params.radius(cursor.next_pressure(curr, &params))))
+        .collect()
+}
 
-    // Cap and taper settings
-    let cap_start = start_options.cap.unwrap_or(true);
-    let cap_end = end_options.cap.unwrap_or(true);
+/// The radius of the stroke at each of its points as it's drawn, after
+/// tapering. The first point keeps the radius of its cap.
+pub(crate) fn get_tapered_stroke_radii(
+    points: &[StrokePoint],
+    options: &StrokeOptions,
+) -> Vec<f64> {
+    let params = OutlineParams::new(points, options);
+    let mut radii = get_stroke_radii(points, options);
+    for (radius, point) in radii.iter_mut().zip(points).skip(1) {
+        *radius = params.taper(*radius, point.running_length);
+    }
 
-    // Taper start easing
-    let taper_start_ease = start_options.easing.unwrap_or(|t| t * (2.0 - t));
-    
-    // Taper end easing
-    let taper_end_ease = end_options.easing.unwrap_or(|t| 1.0 - (1.0 - t).powi(3));
+    radii
+}
 
+fn outline_with_params(points: &[StrokePoint], params: &OutlineParams) -> StrokeOutline {
     // We can't do anything with an empty array or a stroke with negative size
-    if points.is_empty() || size <= 0.0 {
-        return vec![];
+    if points.is_empty() || params.size <= 0.0 {
+        return StrokeOutline::default();
     }
 
-    // The total length of the line
-    let total_length = points.last().map(|p| p.running_length).unwrap_or(0.0);
+    // Our collected left and right points
+    let mut left = OutlineSide::default();
+    let mut right = OutlineSide::default();
 
-    // The minimum allowed distance between points (squared)
-    let min_distance = (size * smoothing).powi(2);
+    // Iterate through the points and generate the outline, skipping the first point
+    let mut cursor = OutlineCursor::new(points, params);
+    for curr in points.iter().skip(1) {
+        cursor.push(curr, params, &mut left, &mut right);
+    }
+    cursor.finish(&mut left, &mut right);
 
-    // Our collected left and right points
-    let mut left_pts: Vec<[f64; 2]> = Vec::new();
-    let mut right_pts: Vec<[f64; 2]> = Vec::new();
+    let start_cap = start_cap(points, params);
+    let end_cap = if right.is_empty() {
+        Vec::new()
+    } else {
+        end_cap(points, params)
+    };
 
-    // Previous pressure (start with average of first five pressures,
-    // in order to prevent fat starts for every line. Drawn lines
-    // almost always start slow!
-    let mut prev_pressure = points.iter().take(10).fold(points[0].pressure, |acc, curr| {
-        let mut pressure = curr.pressure;
+    StrokeOutline {
+        left: left.points,
+        left_indices: left.sources,
+        right: right.points,
+        right_indices: right.sources,
+        start_cap,
+        end_cap,
+    }
+}
 
-        if simulate_pressure {
-            // Speed of change - how fast should the the pressure changing?
-            let sp = f64::min(1.0, curr.distance / size);
-            // Rate of change - how much of a change is there?
-            let rp = f64::min(1.0, 1.0 - sp);
-            // Accelerate the pressure
-            pressure = f64::min(1.0, acc + (rp - acc) * (sp * RATE_OF_PRESSURE_CHANGE));
-        }
+/// Get the outer and inner rings around the stroke points of a closed loop,
+/// as returned by `get_closed_stroke_points`. The line has no caps or tapers.
+pub(crate) fn get_closed_outline_points(
+    points: &[StrokePoint],
+    options: &StrokeOptions,
+) -> ClosedStroke {
+    let mut params = OutlineParams::new(points, options);
+    params.taper_start = 0.0;
+    params.taper_end = 0.0;
 
-        (acc + pressure) / 2.0
-    });
+    if points.is_empty() || params.size <= 0.0 {
+        return ClosedStroke::default();
+    }
 
-    // Calculate the first point's radius for the start cap
-    let first_point_radius = if thinning > 0.0 {
-        get_stroke_radius(size, thinning, points[0].pressure, Some(easing_fn))
-    } else {
-        size / 2.0
-    };
+    let mut left_pts = OutlineSide::default();
+    let mut right_pts = OutlineSide::default();
 
-    // Previous vector
-    let mut prev_vector = points[0].vector;
+    // Every point is offset, including the first, which turns from the last
+    let mut cursor = OutlineCursor::new_loop(points, &params);
+    for curr in points {
+        cursor.push(curr, &params, &mut left_pts, &mut right_pts);
+    }
+    cursor.finish(&mut left_pts, &mut right_pts);
 
-    // Previous left and right points
-    let mut pl = points[0].point;
-    let mut pr = pl;
+    let left_ring = close_ring(left_pts.points, params.min_distance);
+    let right_ring = close_ring(right_pts.points, params.min_distance);
 
-    // Keep track of whether the previous point is a sharp corner
-    // ... so that we don't detect the same corner twice
-    let mut is_prev_sharp_corner = false;
-
-    // Determine taper settings
-    let taper_start = match &start_options.taper {
-        Some(taper) => match taper {
-            crate::types::TaperType::Bool(false) => 0.0,
-            crate::types::TaperType::Bool(true) => f64::max(size, total_length),
-            crate::types::TaperType::Number(value) => *value,
-        },
-        None => 0.0,
+    // Wind the outer ring counter-clockwise and the inner ring clockwise
+    let is_left_outer = signed_area(&left_ring).abs() >= signed_area(&right_ring).abs();
+    let (mut outer, mut inner) = if is_left_outer {
+        (left_ring, right_ring)
+    } else {
+        (right_ring, left_ring)
     };
 
-    let taper_end = match &end_options.taper {
-        Some(taper) => match taper {
-            crate::types::TaperType::Bool(false) => 0.0,
-            crate::types::TaperType::Bool(true) => f64::max(size, total_length),
-            crate::types::TaperType::Number(value) => *value,
-        },
-        None => 0.0,
-    };
+    if signed_area(&outer) < 0.0 {
+        outer.reverse();
+    }
+    if signed_area(&inner) > 0.0 {
+        inner.reverse();
+    }
 
-    // Iterate through the points and generate the outline
-    for (i, curr) in points.iter().enumerate() {
-        // Skip the first point
-        if i == 0 {
-            continue;
-        }
+    ClosedStroke { outer, inner }
+}
 
-        // Get the current point and vector
-        let point = curr.point;
-        let vector = curr.vector;
-        let distance = curr.distance;
-        let running_length = curr.running_length;
+/// Drop the points at the end of a ring that come back too close to its first
+/// point, then repeat the first point to close it.
+fn close_ring(mut ring: Vec<[f64; 2]>, min_distance: f64) -> Vec<[f64; 2]> {
+    while ring.len() > 3 && dist2(ring[0], ring[ring.len() - 1]) <= min_distance {
+        ring.pop();
+    }
 
-        // Calculate the current pressure
-        let mut pressure = curr.pressure;
+    if let Some(&first) = ring.first() {
+        ring.push(first);
+    }
 
-        // Simulate pressure if needed
-        if thinning > 0.0 && simulate_pressure {
-            let sp = f64::min(1.0, distance / size);
-            let rp = f64::min(1.0, 1.0 - sp);
-            pressure = f64::min(
-                1.0,
-                prev_pressure + (rp - prev_pressure) * (sp * RATE_OF_PRESSURE_CHANGE),
-            );
+    ring
+}
+
+/// The settings used while building an outline, resolved from the options
+/// and the length of the stroke.
+pub(crate) struct OutlineParams {
+    pub(crate) size: f64,
+    thinning: f64,
+    simulate_pressure: bool,
+    // Use each point's pressure as its radius
+    pressure_is_radius: bool,
+    pressure_simulation: PressureSimulation,
+    nib: Nib,
+    // No join keeps the offset points at corners, as before joins were added
+    join: Option<JoinStyle>,
+    easing_fn: fn(f64) -> f64,
+    taper_start_ease: fn(f64) -> f64,
+    taper_end_ease: fn(f64) -> f64,
+    cap_start: bool,
+    cap_end: bool,
+    // No start cap style keeps the original round start cap
+    cap_start_style: Option<CapStyle>,
+    cap_end_style: CapStyle,
+    close_path: bool,
+    // The minimum allowed distance between points (squared)
+    min_distance: f64,
+    // The total length of the line
+    total_length: f64,
+    pub(crate) taper_start: f64,
+    pub(crate) taper_end: f64,
+}
+
+impl OutlineParams {
+    pub(crate) fn new(points: &[StrokePoint], options: &StrokeOptions) -> Self {
+        let size = options.size.unwrap_or(16.0);
+        let smoothing = options.smoothing.unwrap_or(0.5);
+
+        // Get start and end options with defaults
+        let start_options = options.start.clone().unwrap_or_default();
+        let end_options = options.end.clone().unwrap_or_default();
+
+        let total_length = points.last().map(|p| p.running_length).unwrap_or(0.0);
+
+        Self {
+            size,
+            thinning: options.thinning.unwrap_or(0.5),
+            simulate_pressure: options.simulate_pressure.unwrap_or(true),
+            pressure_is_radius: false,
+            pressure_simulation: options.pressure_simulation.unwrap_or_default(),
+            nib: options.nib.unwrap_or_default(),
+            join: options.join,
+            // Define the easing function or use the default (identity function)
+            easing_fn: options.easing.unwrap_or(|t| t),
+            // Taper start easing
+            taper_start_ease: start_options.easing.unwrap_or(|t| t * (2.0 - t)),
+            // Taper end easing
+            taper_end_ease: end_options.easing.unwrap_or(|t| 1.0 - (1.0 - t).powi(3)),
+            cap_start: start_options.cap.unwrap_or(true),
+            cap_end: end_options.cap.unwrap_or(true),
+            cap_start_style: start_options.cap_style,
+            cap_end_style: end_options.cap_style.unwrap_or_default(),
+            close_path: options.closed.unwrap_or(false),
+            min_distance: (size * smoothing).powi(2),
+            total_length,
+            taper_start: taper_distance(&start_options.taper, size, total_length),
+            taper_end: taper_distance(&end_options.taper, size, total_length),
         }
+    }
 
-        prev_pressure = pressure;
-
-        // Calculate the current radius
-        let radius = if thinning > 0.0 {
-            get_stroke_radius(size, thinning, pressure, Some(easing_fn))
+    /// The radius for a given pressure, before tapering.
+    fn radius(&self, pressure: f64) -> f64 {
+        if self.pressure_is_radius {
+            pressure
+        } else if self.thinning > 0.0 {
+            get_stroke_radius(self.size, self.thinning, pressure, Some(self.easing_fn))
         } else {
-            size / 2.0
-        };
+            self.size / 2.0
+        }
+    }
 
-        // Apply tapering if needed
-        let ts = if running_length < taper_start {
-            taper_start_ease(running_length / taper_start)
+    /// The radius after tapering toward the ends of the line.
+    fn taper(&self, radius: f64, running_length: f64) -> f64 {
+        let ts = if running_length < self.taper_start {
+            (self.taper_start_ease)(running_length / self.taper_start)
         } else {
             1.0
         };
 
-        let te = if total_length - running_length < taper_end {
-            taper_end_ease((total_length - running_length) / taper_end)
+        let te = if self.total_length - running_length < self.taper_end {
+            (self.taper_end_ease)((self.total_length - running_length) / self.taper_end)
         } else {
             1.0
         };
 
-        let radius = f64::max(0.01, radius * f64::min(ts, te));
+        f64::max(0.01, radius * f64::min(ts, te))
+    }
+
+    /// The shape of an elliptical tip with the given radius, as the direction of
+    /// its long axis and its two semi-axes, or `None` for a round tip.
+    fn nib_axes(
+        &self,
+        radius: f64,
+        orientation: Option<PenOrientation>,
+    ) -> Option<([f64; 2], f64, f64)> {
+        let (angle, aspect) = match self.nib {
+            Nib::Round => return None,
+            Nib::Fixed { angle, aspect } => {
+                let twist = orientation.and_then(|o| o.twist).unwrap_or(0.0);
+                (angle + twist.to_radians(), aspect)
+            }
+            Nib::Tilt { aspect } => {
+                let orientation = orientation?;
+                let azimuth = orientation.azimuth_angle()?;
+                let tilt = orientation.tilt_angle().unwrap_or(0.0);
+                (azimuth, 1.0 - (1.0 - aspect) * tilt.sin())
+            }
+        };
+
+        Some(([angle.cos(), angle.sin()], radius, f64::max(0.01, radius * aspect)))
+    }
+
+    /// The offset from a point to the left side of the outline. The right side
+    /// is offset by the same amount in the other direction.
+    fn offset(
+        &self,
+        vector: [f64; 2],
+        radius: f64,
+        orientation: Option<PenOrientation>,
+    ) -> [f64; 2] {
+        let normal = per(vector);
+
+        match self.nib_axes(radius, orientation) {
+            Some((axis, a, b)) => {
+                // The point on the edge of the tip that reaches furthest along the normal
+                let cross = per(axis);
+                let w = [a * dpr(normal, axis), b * dpr(normal, cross)];
+                let l = len(w);
+                add(mul(axis, a * w[0] / l), mul(cross, b * w[1] / l))
+            }
+            None => mul(normal, radius),
+        }
+    }
+
+    /// The points that join the outer side of the outline around a corner,
+    /// from the edge along `vector_in` to the edge along `vector_out`. `side`
+    /// is 1 for the left side and -1 for the right.
+    fn join_points(
+        &self,
+        corner: [f64; 2],
+        vector_in: [f64; 2],
+        vector_out: [f64; 2],
+        radius: f64,
+        side: f64,
+        orientation: Option<PenOrientation>,
+    ) -> Vec<[f64; 2]> {
+        let edge =
+            |vector: [f64; 2]| add(corner, mul(self.offset(vector, radius, orientation), side));
+        let bevel = vec![edge(vector_in), edge(vector_out)];
+
+        match self.join.unwrap_or_default() {
+            JoinStyle::Round => {
+                // Turn the vector from one edge to the other, following the tip around
+                let angle = f64::atan2(cpr(vector_in, vector_out), dpr(vector_in, vector_out));
+                let steps = f64::max(1.0, (angle.abs() / JOIN_ARC_STEP).ceil()) as usize;
+                (0..=steps)
+                    .map(|i| {
+                        let t = i as f64 / steps as f64;
+                        edge(rot_around(vector_in, [0.0, 0.0], angle * t))
+                    })
+                    .collect()
+            }
+            JoinStyle::Bevel => bevel,
+            JoinStyle::Miter { limit } => {
+                match intersect(bevel[0], vector_in, bevel[1], vector_out) {
+                    Some(miter) if dist(miter, corner) <= limit * radius => vec![miter],
+                    _ => bevel,
+                }
+            }
+        }
+    }
+
+    /// The points of a cap around `center`, from the offset `from` on one side
+    /// of the line to the opposite offset on the other, reaching out along
+    /// the unit vector `direction`.
+    fn cap_points(
+        &self,
+        style: CapStyle,
+        center: [f64; 2],
+        from: [f64; 2],
+        direction: [f64; 2],
+        radius: f64,
+        orientation: Option<PenOrientation>,
+    ) -> Vec<[f64; 2]> {
+        let side_a = add(center, from);
+        let side_b = add(center, neg(from));
+
+        match style {
+            CapStyle::Butt => vec![side_a, side_b],
+            CapStyle::Square => {
+                let reach = mul(direction, radius);
+                vec![side_a, add(side_a, reach), add(side_b, reach), side_b]
+            }
+            CapStyle::Round(resolution) => {
+                if let Some(axes) = self.nib_axes(radius, orientation) {
+                    let steps = resolution.segments(axes.1, PI);
+                    return nib_cap(center, axes, from, direction, steps);
+                }
+
+                // Turn the first side around the center until it reaches the other side
+                let turn = if dpr(per(from), direction) > 0.0 {
+                    -FIXED_PI
+                } else {
+                    FIXED_PI
+                };
+                let steps = resolution.segments(radius, PI);
+                (0..=steps)
+                    .map(|i| rot_around(side_a, center, turn * i as f64 / steps as f64))
+                    .collect()
+            }
+        }
+    }
+
+    /// Accelerate the previous pressure toward the pressure for the pen's speed.
+    fn simulate(&self, prev_pressure: f64, distance: f64, elapsed: Option<f64>) -> f64 {
+        match (self.pressure_simulation, elapsed) {
+            (PressureSimulation::Velocity, Some(elapsed)) if elapsed > 0.0 => {
+                // Measure the speed as the distance covered in one frame, and
+                // apply the rate of change once for every frame that has passed
+                let frames = elapsed / FRAME_DURATION;
+                let sp = f64::min(1.0, distance / frames / self.size);
+                let rp = f64::min(1.0, 1.0 - sp);
+                let rate = 1.0 - (1.0 - sp * RATE_OF_PRESSURE_CHANGE).powf(frames);
+                f64::min(1.0, prev_pressure + (rp - prev_pressure) * rate)
+            }
+            _ => {
+                // Speed of change - how fast should the the pressure changing?
+                let sp = f64::min(1.0, distance / self.size);
+                // Rate of change - how much of a change is there?
+                let rp = f64::min(1.0, 1.0 - sp);
+                // Accelerate the pressure
+                f64::min(
+                    1.0,
+                    prev_pressure + (rp - prev_pressure) * (sp * RATE_OF_PRESSURE_CHANGE),
+                )
+            }
+        }
+    }
+}
+
+/// The time between two stroke points, if both have timestamps
+fn elapsed(prev_timestamp: Option<f64>, curr: &StrokePoint) -> Option<f64> {
+    match (prev_timestamp, curr.timestamp) {
+        (Some(prev), Some(curr)) => Some(curr - prev),
+        _ => None,
+    }
+}
+
+/// Determine the taper distance from a taper setting
+fn taper_distance(taper: &Option<TaperType>, size: f64, total_length: f64) -> f64 {
+    match taper {
+        Some(TaperType::Bool(false)) | None => 0.0,
+        Some(TaperType::Bool(true)) => f64::max(size, total_length),
+        Some(TaperType::Number(value)) => *value,
+    }
+}
+
+/// One side of an outline, along with the index of the stroke point that
+/// each of its points came from.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OutlineSide {
+    pub(crate) points: Vec<[f64; 2]>,
+    pub(crate) sources: Vec<usize>,
+}
+
+impl OutlineSide {
+    fn push(&mut self, point: [f64; 2], source: usize) {
+        self.points.push(point);
+        self.sources.push(source);
+    }
+
+    fn last(&self) -> Option<&[f64; 2]> {
+        self.points.last()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.points.truncate(len);
+        self.sources.truncate(len);
+    }
+}
+
+/// The state carried from one stroke point to the next while offsetting
+/// the left and right sides of the outline.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct OutlineCursor {
+    prev_pressure: f64,
+    prev_timestamp: Option<f64>,
+    // Previous vector
+    prev_vector: [f64; 2],
+    // Previous left and right points
+    pl: [f64; 2],
+    pr: [f64; 2],
+    // Keep track of whether the previous point is a sharp corner
+    // ... so that we don't detect the same corner twice
+    is_prev_sharp_corner: bool,
+    // The previous point and its distance from the point before it
+    prev_point: [f64; 2],
+    prev_distance: f64,
+    // The latest left and right points, held back in case the next point
+    // turns a sharp corner
+    held_left: Option<[f64; 2]>,
+    held_right: Option<[f64; 2]>,
+    // The indices of the previous and the next stroke points
+    prev_index: usize,
+    index: usize,
+}
+
+impl OutlineCursor {
+    /// Start at the first of the stroke's points. `points` must not be empty.
+    pub(crate) fn new(points: &[StrokePoint], params: &OutlineParams) -> Self {
+        // Previous pressure (start with average of first five pressures,
+        // in order to prevent fat starts for every line. Drawn lines
+        // almost always start slow!
+        let mut prev_timestamp = points[0].timestamp;
+        let prev_pressure = points.iter().take(10).fold(points[0].pressure, |acc, curr| {
+            let mut pressure = curr.pressure;
+
+            if params.simulate_pressure {
+                pressure = params.simulate(acc, curr.distance, elapsed(prev_timestamp, curr));
+            }
+
+            prev_timestamp = curr.timestamp;
+
+            (acc + pressure) / 2.0
+        });
+
+        Self {
+            prev_pressure,
+            prev_timestamp: points[0].timestamp,
+            prev_vector: points[0].vector,
+            pl: points[0].point,
+            pr: points[0].point,
+            is_prev_sharp_corner: false,
+            prev_point: points[0].point,
+            prev_distance: points[0].distance,
+            held_left: None,
+            held_right: None,
+            prev_index: 0,
+            index: 1,
+        }
+    }
+
+    /// Start a closed loop, where the first point follows on from the last one.
+    /// The simulated pressure is carried once around the loop, so that it
+    /// matches on both sides of the seam.
+    pub(crate) fn new_loop(points: &[StrokePoint], params: &OutlineParams) -> Self {
+        let last = &points[points.len() - 1];
+        let start = Self {
+            prev_timestamp: last.timestamp,
+            prev_vector: last.vector,
+            // Nothing has been offset yet, so the first points are always added
+            pl: [f64::INFINITY; 2],
+            pr: [f64::INFINITY; 2],
+            prev_point: last.point,
+            prev_distance: last.distance,
+            prev_index: points.len() - 1,
+            index: 0,
+            ..Self::new(points, params)
+        };
+
+        let mut cursor = start;
+        let (mut left_pts, mut right_pts) = (OutlineSide::default(), OutlineSide::default());
+        for curr in points {
+            cursor.push(curr, params, &mut left_pts, &mut right_pts);
+        }
+
+        Self {
+            prev_pressure: cursor.prev_pressure,
+            ..start
+        }
+    }
 
-        // Calculate the normal vector for this point
-        let normal_vector = per(vector);
+    /// Offset the next stroke point, adding any new points to the left and right sides.
+    pub(crate) fn push(
+        &mut self,
+        curr: &StrokePoint,
+        params: &OutlineParams,
+        left_pts: &mut OutlineSide,
+        right_pts: &mut OutlineSide,
+    ) {
+        let min_distance = params.min_distance;
+
+        // Get the current point and vector
+        let point = curr.point;
+        let vector = curr.vector;
+        let distance = curr.distance;
+        let running_length = curr.running_length;
+
+        // Calculate the current radius
+        let radius = params.radius(self.next_pressure(curr, params));
+
+        // Apply tapering if needed
+        let radius = params.taper(radius, running_length);
 
         // Calculate the offset points for this point
-        let offset_vector = mul(normal_vector, radius);
+        let offset_vector = params.offset(vector, radius, curr.orientation);
         let left_point = add(point, offset_vector);
         let right_point = add(point, neg(offset_vector));
 
         // Check if we need to handle sharp corners
-        let is_sharp_corner = dpr(prev_vector, vector) < 0.0;
+        let is_sharp_corner = dpr(self.prev_vector, vector) < 0.0;
 
-        if is_sharp_corner && !is_prev_sharp_corner {
+        if is_sharp_corner && !self.is_prev_sharp_corner && params.join.is_none() {
             // Add the last point - skip if too close to the previous point
-            if dist2(left_point, pl) > min_distance {
-                left_pts.push(left_point);
-                pl = left_point;
+            self.finish(left_pts, right_pts);
+
+            if dist2(left_point, self.pl) > min_distance {
+                left_pts.push(left_point, self.index);
+                self.pl = left_point;
             }
 
-            if dist2(right_point, pr) > min_distance {
-                right_pts.push(right_point);
-                pr = right_point;
+            if dist2(right_point, self.pr) > min_distance {
+                right_pts.push(right_point, self.index);
+                self.pr = right_point;
             }
+        } else if is_sharp_corner && !self.is_prev_sharp_corner {
+            // The corner is at the previous point, where the line turns
+            // from the previous vector to this one. The line turns away
+            // from the outer side, so that's the side that gets the join.
+            let corner = self.prev_point;
+            let is_outer_left = cpr(self.prev_vector, vector) < 0.0;
+            let offset_in = params.offset(self.prev_vector, radius, curr.orientation);
+            let offset_out = params.offset(vector, radius, curr.orientation);
+
+            let (outer, outer_held, inner, inner_held, side) = if is_outer_left {
+                (&mut *left_pts, &mut self.held_left, &mut *right_pts, &mut self.held_right, 1.0)
+            } else {
+                (&mut *right_pts, &mut self.held_right, &mut *left_pts, &mut self.held_left, -1.0)
+            };
+
+            // Join the outer side around the corner
+            if let Some(held) = outer_held.take() {
+                outer.push(held, self.prev_index);
+            }
+            let orientation = curr.orientation;
+            let join = params.join_points(corner, self.prev_vector, vector, radius, side, orientation);
+            for join_point in join {
+                if outer.last() != Some(&join_point) {
+                    outer.push(join_point, self.prev_index);
+                }
+            }
+
+            // Meet the inner side where its two edges cross, in place of
+            // the offset point held back from the previous point
+            *inner_held = None;
+            let arm = f64::min(self.prev_distance, distance);
+            let inner_point = inner_corner(
+                corner,
+                mul(offset_in, -side),
+                mul(offset_out, -side),
+                self.prev_vector,
+                vector,
+                radius,
+                arm,
+            );
+            inner.push(inner_point, self.prev_index);
+
+            self.pl = *left_pts.last().unwrap_or(&self.pl);
+            self.pr = *right_pts.last().unwrap_or(&self.pr);
         } else {
             // We're in a curve (or straight line)
+            self.finish(left_pts, right_pts);
 
-            if !is_prev_sharp_corner {
+            if !self.is_prev_sharp_corner {
                 // Create the next offset point
-                let prev_normal = per(prev_vector);
-                let offset_a = mul(prev_normal, radius);
-                
+                let offset_a = params.offset(self.prev_vector, radius, curr.orientation);
+
                 // Calculate temporary left and right points
                 let tl = add(point, offset_a);
                 let tr = add(point, neg(offset_a));
 
                 // Add the previous offset points
-                if dist2(pl, tl) > min_distance {
-                    left_pts.push(tl);
-                    pl = tl;
+                if dist2(self.pl, tl) > min_distance {
+                    left_pts.push(tl, self.index);
+                    self.pl = tl;
                 }
 
-                if dist2(pr, tr) > min_distance {
-                    right_pts.push(tr);
-                    pr = tr;
+                if dist2(self.pr, tr) > min_distance {
+                    right_pts.push(tr, self.index);
+                    self.pr = tr;
                 }
             }
+        }
 
-            // Add the current offset points
-            if dist2(pl, left_point) > min_distance {
-                left_pts.push(left_point);
-                pl = left_point;
-            }
+        // Add the current offset points, holding them back in case the
+        // next point turns a corner
+        if dist2(self.pl, left_point) > min_distance {
+            self.held_left = Some(left_point);
+            self.pl = left_point;
+        }
 
-            if dist2(pr, right_point) > min_distance {
-                right_pts.push(right_point);
-                pr = right_point;
-            }
+        if dist2(self.pr, right_point) > min_distance {
+            self.held_right = Some(right_point);
+            self.pr = right_point;
         }
 
         // Set variables for the next iteration
-        prev_vector = vector;
-        is_prev_sharp_corner = is_sharp_corner;
-    }
-
-    // Add caps if needed
-    let mut result = Vec::new();
-    
-    // Get the closed flag
-    let close_path = options.closed.unwrap_or(false);
-
-    // Start cap
-    if cap_start {
-        let first_point = points[0].point;
-        let first_normal = per(points[0].vector);
-        let offset_vector = mul(first_normal, first_point_radius);
-
-        let start_left = add(first_point, offset_vector);
-        let start_right = add(first_point, neg(offset_vector));
-
-        // Add the start cap (from left to right)
-        result.push(start_left);
-        
-        // Add semicircular cap
-        if points.len() > 1 {
-            let steps = 4;
-            for i in 0..=steps {
-                let t = i as f64 / steps as f64;
-                let angle = FIXED_PI - t * FIXED_PI;
-                result.push(rot_around(start_right, first_point, angle));
-            }
-        } else {
-            result.push(start_right);
+        self.prev_vector = vector;
+        self.prev_point = point;
+        self.prev_distance = distance;
+        self.is_prev_sharp_corner = is_sharp_corner;
+        self.prev_index = self.index;
+        self.index += 1;
+    }
+
+    /// The pressure at the next stroke point, simulated if needed.
+    fn next_pressure(&mut self, curr: &StrokePoint, params: &OutlineParams) -> f64 {
+        let mut pressure = curr.pressure;
+
+        if params.thinning > 0.0 && params.simulate_pressure {
+            let elapsed = elapsed(self.prev_timestamp, curr);
+            pressure = params.simulate(self.prev_pressure, curr.distance, elapsed);
         }
+
+        self.prev_pressure = pressure;
+        self.prev_timestamp = curr.timestamp;
+
+        pressure
     }
 
-    // Add right side points (from start to end)
-    for p in right_pts.iter() {
-        result.push(*p);
+    /// Add the offset points that were held back, once there are no more points.
+    pub(crate) fn finish(&mut self, left_pts: &mut OutlineSide, right_pts: &mut OutlineSide) {
+        if let Some(held) = self.held_left.take() {
+            left_pts.push(held, self.prev_index);
+        }
+        if let Some(held) = self.held_right.take() {
+            right_pts.push(held, self.prev_index);
+        }
     }
-    
-    // End cap
-    if cap_end && !right_pts.is_empty() {
-        let last_point = points.last().map(|p| p.point).unwrap_or_default();
-        let last_vector = points.last().map(|p| p.vector).unwrap_or_default();
-        let last_normal = per(last_vector);
-        
-        let last_radius = if points.len() > 1 {
-            let last_pressure = points.last().map(|p| p.pressure).unwrap_or(0.5);
-            if thinning > 0.0 {
-                get_stroke_radius(size, thinning, last_pressure, Some(easing_fn))
-            } else {
-                size / 2.0
-            }
-        } else {
-            first_point_radius
-        };
+}
 
-        let tapered_radius = if taper_end > 0.0 {
-            0.01
-        } else {
-            last_radius
-        };
+/// Where the line through `a` along `da` crosses the line through `b` along `db`
+fn intersect(a: [f64; 2], da: [f64; 2], b: [f64; 2], db: [f64; 2]) -> Option<[f64; 2]> {
+    let denominator = cpr(da, db);
+    if denominator.abs() < 1e-9 {
+        return None;
+    }
 
-        let offset_vector = mul(last_normal, tapered_radius);
-        let end_right = add(last_point, neg(offset_vector));
-        let end_left = add(last_point, offset_vector);
+    let t = cpr(sub(b, a), db) / denominator;
+    Some(add(a, mul(da, t)))
+}
+
+/// The point where the inner edges meet at a corner. The edges only run as far
+/// as the shorter `arm` of the corner, so on tight corners the point is pulled
+/// back toward the corner instead of reaching past the ends of the arms.
+fn inner_corner(
+    corner: [f64; 2],
+    offset_in: [f64; 2],
+    offset_out: [f64; 2],
+    vector_in: [f64; 2],
+    vector_out: [f64; 2],
+    radius: f64,
+    arm: f64,
+) -> [f64; 2] {
+    let Some(crossing) = intersect(
+        add(corner, offset_in),
+        vector_in,
+        add(corner, offset_out),
+        vector_out,
+    ) else {
+        return corner;
+    };
 
-        // Add semicircular cap (from right to left)
-        if points.len() > 1 {
-            let steps = 4;
-            for i in 0..=steps {
-                let t = i as f64 / steps as f64;
-                result.push(rot_around(end_right, last_point, t * FIXED_PI));
-            }
-        } else {
-            result.push(end_left);
-        }
+    let reach = sub(crossing, corner);
+    let max_reach = (radius * radius + arm * arm).sqrt();
+    if len(reach) > max_reach {
+        add(corner, mul(uni(reach), max_reach))
+    } else {
+        crossing
     }
-    
-    // Add left side points (from end to start)
-    for p in left_pts.iter().rev() {
-        result.push(*p);
+}
+
+/// The start cap, from the left side around to the right side.
+pub(crate) fn start_cap(points: &[StrokePoint], params: &OutlineParams) -> Vec<[f64; 2]> {
+    if !params.cap_start || points.is_empty() {
+        return Vec::new();
     }
-    
-    // Close the path if needed
-    if close_path && !result.is_empty() && result.len() > 1 {
-        result.push(result[0]);
+
+    // Calculate the first point's radius for the start cap
+    let first_point_radius = params.radius(points[0].pressure);
+
+    let first_point = points[0].point;
+    let first_vector = points[0].vector;
+    let orientation = points[0].orientation;
+    let offset_vector = params.offset(first_vector, first_point_radius, orientation);
+
+    let start_left = add(first_point, offset_vector);
+    let start_right = add(first_point, neg(offset_vector));
+
+    if points.len() == 1 {
+        return vec![start_left, start_right];
+    }
+
+    // Without a cap style, the original round cap starts at the left side,
+    // then turns the right side around the first point until it meets it
+    let is_round_tip = params.nib_axes(first_point_radius, orientation).is_none();
+    if params.cap_start_style.is_none() && is_round_tip {
+        let steps = 4;
+        let arc = (0..=steps).map(|i| {
+            let t = i as f64 / steps as f64;
+            rot_around(start_right, first_point, FIXED_PI - t * FIXED_PI)
+        });
+        return std::iter::once(start_left).chain(arc).collect();
+    }
+
+    // The first point's vector points back, away from the line
+    params.cap_points(
+        params.cap_start_style.unwrap_or_default(),
+        first_point,
+        offset_vector,
+        first_vector,
+        first_point_radius,
+        orientation,
+    )
+}
+
+/// The end cap, from the right side around to the left side.
+pub(crate) fn end_cap(points: &[StrokePoint], params: &OutlineParams) -> Vec<[f64; 2]> {
+    let Some(last) = points.last() else {
+        return Vec::new();
+    };
+
+    if !params.cap_end {
+        return Vec::new();
     }
-    // If not explicitly closed, ensure we close it for testing purposes
-    else if !result.is_empty() && result.len() > 1 && result[0] != result[result.len() - 1] {
+
+    let last_point = last.point;
+
+    let last_radius = if points.len() > 1 {
+        params.radius(last.pressure)
+    } else {
+        params.radius(points[0].pressure)
+    };
+
+    let tapered_radius = if params.taper_end > 0.0 {
+        0.01
+    } else {
+        last_radius
+    };
+
+    let offset_vector = params.offset(last.vector, tapered_radius, last.orientation);
+
+    if points.len() == 1 {
+        return vec![add(last_point, offset_vector)];
+    }
+
+    // The last point's vector points back along the line
+    params.cap_points(
+        params.cap_end_style,
+        last_point,
+        neg(offset_vector),
+        neg(last.vector),
+        tapered_radius,
+        last.orientation,
+    )
+}
+
+/// Points around half of an elliptical tip centered on `center`, starting
+/// at the offset `from` and going around the side that faces `direction`.
+fn nib_cap(
+    center: [f64; 2],
+    (axis, a, b): ([f64; 2], f64, f64),
+    from: [f64; 2],
+    direction: [f64; 2],
+    steps: usize,
+) -> Vec<[f64; 2]> {
+    let cross = per(axis);
+    let point_at = |t: f64| add(mul(axis, a * t.cos()), mul(cross, b * t.sin()));
+
+    let start = f64::atan2(dpr(from, cross) / b, dpr(from, axis) / a);
+    let turn = if dpr(point_at(start + PI / 2.0), direction) < 0.0 {
+        -PI
+    } else {
+        PI
+    };
+
+    (0..=steps)
+        .map(|i| add(center, point_at(start + turn * i as f64 / steps as f64)))
+        .collect()
+}
+
+/// Join the caps and the two sides into a single polygon.
+pub(crate) fn assemble_outline(
+    params: &OutlineParams,
+    start_cap: &[[f64; 2]],
+    right_pts: &[[f64; 2]],
+    end_cap: &[[f64; 2]],
+    left_pts: &[[f64; 2]],
+) -> Vec<[f64; 2]> {
+    let mut result = start_cap.to_vec();
+
+    // Add right side points (from start to end)
+    result.extend_from_slice(right_pts);
+
+    // End cap
+    result.extend_from_slice(end_cap);
+
+    // Add left side points (from end to start)
+    result.extend(left_pts.iter().rev());
+
+    // Close the path if needed. If not explicitly closed, we still
+    // close it for testing purposes
+    if result.len() > 1 && (params.close_path || result[0] != result[result.len() - 1]) {
         result.push(result[0]);
     }
 
     result
-} 
\ No newline at end of file
+}
@@ -1,4 +1,7 @@
-use crate::types::{InputPoint, StrokeOptions, StrokePoint};
+use crate::find_end_hook::find_end_hook;
+use crate::resample_points::resample_points;
+use crate::smoother::Smoother;
+use crate::types::{InputPoint, PenOrientation, SmootherType, StrokeOptions, StrokePoint};
 use crate::vec::{add, dist, is_equal, lrp, sub, uni};
 
 /// Get an array of points as objects with an adjusted point, pressure, vector, distance, and running_length.
@@ -13,8 +16,6 @@ pub fn get_stroke_points(
     points: &[InputPoint],
     options: &StrokeOptions,
 ) -> Vec<StrokePoint> {
-    let streamline = options.streamline.unwrap_or(0.5);
-    let size = options.size.unwrap_or(16.0);
     let is_complete = options.last.unwrap_or(false);
 
     // If we don't have any points, return an empty array
@@ -22,17 +23,27 @@ pub fn get_stroke_points(
         return Vec::new();
     }
 
-    // Find the interpolation level between points
-    let t = 0.15 + (1.0 - streamline) * 0.85;
+    // Trim the hook a stylus can leave as it lifts, once the stroke is done
+    let points = match options.hook_removal.as_ref().filter(|_| is_complete) {
+        Some(hook_removal) => match find_end_hook(points, hook_removal) {
+            Some(hook) => &points[..hook],
+            None => points,
+        },
+        None => points,
+    };
 
-    // Convert all input points to a consistent format [x, y, pressure]
-    let mut pts: Vec<([f64; 2], f64)> = points
-        .iter()
-        .map(|p| match p {
-            InputPoint::Array(point, pressure) => (*point, pressure.unwrap_or(0.5)),
-            InputPoint::Struct { x, y, pressure } => ([*x, *y], pressure.unwrap_or(0.5)),
-        })
-        .collect();
+    // Space out the input points first, if asked to
+    let resampled;
+    let points = match options.resampling.as_ref() {
+        Some(resampling) => {
+            resampled = resample_points(points, resampling);
+            &resampled[..]
+        }
+        None => points,
+    };
+
+    // Convert all input points to a consistent format
+    let mut pts: Vec<NormalizedPoint> = points.iter().map(normalize_point).collect();
 
     // Add extra points between the two, to help avoid "dash" lines
     // for strokes with tapered start and ends
@@ -41,96 +52,293 @@ pub fn get_stroke_points(
         pts = vec![pts[0]];
         for i in 1..5 {
             let t = i as f64 / 4.0;
-            let lerp_point = lrp(pts[0].0, last.0, t);
-            pts.push((lerp_point, last.1));
+            pts.push(NormalizedPoint {
+                point: lrp(pts[0].point, last.point, t),
+                pressure: last.pressure,
+                timestamp: match (pts[0].timestamp, last.timestamp) {
+                    (Some(a), Some(b)) => Some(a + (b - a) * t),
+                    _ => last.timestamp,
+                },
+                orientation: last.orientation,
+            });
         }
     }
 
     // If there's only one point, add another point at a 1pt offset
     if pts.len() == 1 {
         let point = pts[0];
-        let new_point = add(point.0, [1.0, 1.0]);
-        pts.push((new_point, point.1));
+        pts.push(NormalizedPoint {
+            point: add(point.point, [1.0, 1.0]),
+            ..point
+        });
     }
 
     // The stroke_points array will hold the points for the stroke
     // Start it out with the first point, which needs no adjustment
-    let mut stroke_points = vec![StrokePoint {
-        point: [pts[0].0[0], pts[0].0[1]],
-        pressure: if pts[0].1 >= 0.0 { pts[0].1 } else { 0.25 },
-        vector: [1.0, 1.0],
-        distance: 0.0,
-        running_length: 0.0,
-    }];
+    let mut state = StrokePointsState::new(pts[0], options);
+    let mut stroke_points = vec![state.prev.clone()];
 
-    // A flag to see whether we've already reached out minimum length
-    let mut has_reached_minimum_length = false;
+    let max = pts.len() - 1;
+
+    // Iterate through all of the points, creating StrokePoints
+    for (i, pt) in pts.iter().enumerate().skip(1) {
+        state.push(*pt, i == max, is_complete, &mut stroke_points);
+    }
+
+    set_first_vector(&mut stroke_points);
+
+    stroke_points
+}
+
+/// An input point in a consistent format, with the default pressure filled in
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct NormalizedPoint {
+    pub(crate) point: [f64; 2],
+    pub(crate) pressure: f64,
+    pub(crate) timestamp: Option<f64>,
+    pub(crate) orientation: Option<PenOrientation>,
+}
+
+/// Get the stroke points for a closed loop, where the line runs from the last
+/// point back to the first. The streamline smoothing carries on across the
+/// seam, and the first point's vector and distance come from the last point.
+///
+/// Returns `None` if there are fewer than three distinct points.
+pub(crate) fn get_closed_stroke_points(
+    points: &[InputPoint],
+    options: &StrokeOptions,
+) -> Option<Vec<StrokePoint>> {
+    let streamline = options.streamline.unwrap_or(0.5);
+    let t = 0.15 + (1.0 - streamline) * 0.85;
+
+    let pts: Vec<NormalizedPoint> = match options.resampling.as_ref() {
+        Some(resampling) if !points.is_empty() => {
+            // Resample the line back to the first point too, then leave
+            // out the first point's copy at the end
+            let mut looped = points.to_vec();
+            looped.push(points[0].clone());
+            let resampled = resample_points(&looped, resampling);
+            resampled[..resampled.len() - 1].iter().map(normalize_point).collect()
+        }
+        _ => points.iter().map(normalize_point).collect(),
+    };
+
+    if pts.len() < 3 {
+        return None;
+    }
+
+    // Run the smoothing once around the loop, so that it has settled
+    // by the time it reaches the first point again
+    let mut prev = pts.iter().fold(pts[0].point, |prev, pt| lrp(prev, pt.point, t));
+
+    let mut smoothed: Vec<NormalizedPoint> = Vec::with_capacity(pts.len());
+    for pt in &pts {
+        let point = lrp(prev, pt.point, t);
+
+        // If the new point is the same as the previous point, skip ahead
+        if is_equal(prev, point) {
+            continue;
+        }
+
+        prev = point;
+        smoothed.push(NormalizedPoint { point, ..*pt });
+    }
+
+    // The seam shouldn't be a point twice
+    while smoothed.len() > 1 && is_equal(smoothed[0].point, smoothed[smoothed.len() - 1].point) {
+        smoothed.pop();
+    }
+
+    if smoothed.len() < 3 {
+        return None;
+    }
 
-    // We use the running_length to keep track of the total distance
     let mut running_length = 0.0;
+    let stroke_points = smoothed
+        .iter()
+        .enumerate()
+        .map(|(i, curr)| {
+            let prev = smoothed[(i + smoothed.len() - 1) % smoothed.len()].point;
+            let distance = dist(curr.point, prev);
+
+            if i > 0 {
+                running_length += distance;
+            }
+
+            StrokePoint {
+                point: curr.point,
+                pressure: if curr.pressure >= 0.0 { curr.pressure } else { 0.5 },
+                vector: uni(sub(prev, curr.point)),
+                distance,
+                running_length,
+                timestamp: curr.timestamp,
+                orientation: curr.orientation,
+            }
+        })
+        .collect();
 
+    Some(stroke_points)
+}
+
+/// Convert an input point to a consistent format
+pub(crate) fn normalize_point(point: &InputPoint) -> NormalizedPoint {
+    NormalizedPoint {
+        point: point.point(),
+        pressure: point.pressure().unwrap_or(0.5),
+        timestamp: point.timestamp(),
+        orientation: point.orientation(),
+    }
+}
+
+/// Set the vector of the first point to be the same as the second point
+pub(crate) fn set_first_vector(stroke_points: &mut [StrokePoint]) {
+    let vector = match stroke_points.get(1) {
+        Some(second) => second.vector,
+        None => [1.0, 1.0],
+    };
+
+    if let Some(first) = stroke_points.first_mut() {
+        first.vector = vector;
+    }
+}
+
+/// The state carried between input points while building stroke points.
+///
+/// `get_stroke_points` runs through every input point at once, while
+/// `StrokeBuilder` keeps a copy around so that new input points can be
+/// added without starting over.
+#[derive(Debug, Clone)]
+pub(crate) struct StrokePointsState {
+    // The interpolation level between points
+    t: f64,
+    // The filter that smooths the input points in place of streamlining
+    smoother: Option<Box<dyn Smoother>>,
+    size: f64,
+    backfill: bool,
+    // A flag to see whether we've already reached out minimum length
+    has_reached_minimum_length: bool,
+    // We use the running_length to keep track of the total distance
+    running_length: f64,
     // We're set this to the latest point, so we can use it to calculate
     // the distance and vector of the next point
-    let mut prev = stroke_points[0].clone();
+    prev: StrokePoint,
+    // The points at the start of the line that are held back until it
+    // reaches its minimum length, when backfilling
+    held: Vec<StrokePoint>,
+}
 
-    let max = pts.len() - 1;
+impl StrokePointsState {
+    /// Start a new stroke at the first input point, which needs no adjustment.
+    pub(crate) fn new(first: NormalizedPoint, options: &StrokeOptions) -> Self {
+        let streamline = options.streamline.unwrap_or(0.5);
+        let size = options.size.unwrap_or(16.0);
 
-    // Iterate through all of the points, creating StrokePoints
-    for i in 1..pts.len() {
-        let point = if is_complete && i == max {
+        let mut smoother = options.smoother.as_ref().and_then(SmootherType::build);
+        if let Some(smoother) = smoother.as_mut() {
+            smoother.start(first.point, first.timestamp);
+        }
+
+        Self {
+            // Find the interpolation level between points
+            t: 0.15 + (1.0 - streamline) * 0.85,
+            smoother,
+            size,
+            backfill: options.backfill.unwrap_or(false),
+            has_reached_minimum_length: false,
+            running_length: 0.0,
+            prev: StrokePoint {
+                point: first.point,
+                pressure: if first.pressure >= 0.0 { first.pressure } else { 0.25 },
+                vector: [1.0, 1.0],
+                distance: 0.0,
+                running_length: 0.0,
+                timestamp: first.timestamp,
+                orientation: first.orientation,
+            },
+            held: Vec::new(),
+        }
+    }
+
+    /// The first stroke point, or the latest one once points have been added.
+    pub(crate) fn prev(&self) -> &StrokePoint {
+        &self.prev
+    }
+
+    /// Add the next input point, pushing any new stroke points to `stroke_points`.
+    ///
+    /// `is_max` marks the final input point of the stroke so far, which is never
+    /// skipped and, when `is_complete` is set, is used without interpolation.
+    pub(crate) fn push(
+        &mut self,
+        input: NormalizedPoint,
+        is_max: bool,
+        is_complete: bool,
+        stroke_points: &mut Vec<StrokePoint>,
+    ) {
+        let point = if is_complete && is_max {
             // If we're at the last point, and options.last is true,
             // then add the actual input point
-            [pts[i].0[0], pts[i].0[1]]
+            input.point
+        } else if let Some(smoother) = self.smoother.as_mut() {
+            smoother.smooth(input.point, input.timestamp)
         } else {
             // Otherwise, using the t calculated from the streamline
             // option, interpolate a new point between the previous
             // point the current point
-            lrp(prev.point, pts[i].0, t)
+            lrp(self.prev.point, input.point, self.t)
         };
 
         // If the new point is the same as the previous point, skip ahead
-        if is_equal(prev.point, point) {
-            continue;
+        if is_equal(self.prev.point, point) {
+            return;
         }
 
         // How far is the new point from the previous point?
-        let distance = dist(point, prev.point);
+        let distance = dist(point, self.prev.point);
 
         // Add this distance to the total "running length" of the line
-        running_length += distance;
+        self.running_length += distance;
 
         // At the start of the line, we wait until the new point is a
         // certain distance away from the original point, to avoid noise
-        if i < max && !has_reached_minimum_length {
-            if running_length < size {
-                continue;
+        if !is_max && !self.has_reached_minimum_length {
+            if self.running_length < self.size {
+                if self.backfill {
+                    // Hold on to the point, so that it can be added once
+                    // the line is long enough and tapering works correctly
+                    self.prev = self.stroke_point(input, point, distance);
+                    self.held.push(self.prev.clone());
+                }
+                return;
             }
-            has_reached_minimum_length = true;
-            // TODO: Backfill the missing points so that tapering works correctly
+            self.has_reached_minimum_length = true;
         }
 
         // Create a new strokepoint (it will be the new "previous" one)
-        prev = StrokePoint {
+        self.prev = self.stroke_point(input, point, distance);
+
+        // Backfill the points held back at the start of the line, then
+        // push the new point to the stroke_points array
+        stroke_points.append(&mut self.held);
+        stroke_points.push(self.prev.clone());
+    }
+
+    fn stroke_point(&self, input: NormalizedPoint, point: [f64; 2], distance: f64) -> StrokePoint {
+        StrokePoint {
             // The adjusted point
             point,
             // The input pressure (or .5 if not specified)
-            pressure: if pts[i].1 >= 0.0 { pts[i].1 } else { 0.5 },
+            pressure: if input.pressure >= 0.0 { input.pressure } else { 0.5 },
             // The vector from the current point to the previous point
-            vector: uni(sub(prev.point, point)),
+            vector: uni(sub(self.prev.point, point)),
             // The distance between the current point and the previous point
             distance,
             // The total distance so far
-            running_length,
-        };
-
-        // Push it to the stroke_points array
-        stroke_points.push(prev.clone());
-    }
-
-    // Set the vector of the first point to be the same as the second point
-    if let Some(second) = stroke_points.get(1) {
-        stroke_points[0].vector = second.vector;
+            running_length: self.running_length,
+            // The time of the input point
+            timestamp: input.timestamp,
+            // The orientation of the pen at the input point
+            orientation: input.orientation,
+        }
     }
-
-    stroke_points
-} 
\ No newline at end of file
+}
@@ -0,0 +1,95 @@
+use crate::get_stroke_outline_points::get_tapered_stroke_radii;
+use crate::polygon::winding_number;
+use crate::types::{StrokeHit, StrokePoint, StrokeShape};
+use crate::vec::{dist, lrp, nearest_t};
+
+/// Find how far a point is from the edge of a stroke, and the part of the
+/// stroke nearest to it.
+///
+/// # Arguments
+/// * `point` - The point to test, such as the cursor
+/// * `shape` - The stroke's outline, or its centerline and options
+///
+/// # Returns
+/// The nearest part of the stroke, with a negative distance if the point is
+/// inside it, or `None` if the stroke is empty
+pub fn distance_to_stroke(point: [f64; 2], shape: &StrokeShape) -> Option<StrokeHit> {
+    match *shape {
+        StrokeShape::Outline { points, outline } => {
+            let nearest = nearest_on_centerline(point, points, None)?;
+
+            Some(StrokeHit {
+                distance: distance_to_outline(point, outline)?,
+                ..nearest
+            })
+        }
+        StrokeShape::Centerline { points, options } => {
+            let radii = get_tapered_stroke_radii(points, options);
+            nearest_on_centerline(point, points, Some(&radii))
+        }
+    }
+}
+
+/// Check whether a point is on a stroke, or within `tolerance` of its edge
+/// to make thin strokes easier to pick.
+///
+/// # Returns
+/// The nearest part of the stroke if the point is on it, or `None` if not
+pub fn contains_point(point: [f64; 2], shape: &StrokeShape, tolerance: f64) -> Option<StrokeHit> {
+    distance_to_stroke(point, shape).filter(|hit| hit.distance <= tolerance)
+}
+
+/// The distance from a point to the edge of an outline, which is negative
+/// inside it, or `None` if the outline is empty.
+pub(crate) fn distance_to_outline(point: [f64; 2], outline: &[[f64; 2]]) -> Option<f64> {
+    let edge = distance_to_edges(point, outline)?;
+    let is_inside = winding_number(point, outline) != 0;
+
+    Some(if is_inside { -edge } else { edge })
+}
+
+/// The nearest point on the line through the stroke points, where the
+/// distance is measured to the edge of a circle of the given radius around
+/// each point, changing evenly between points, or to the line itself
+/// without any radii.
+fn nearest_on_centerline(
+    point: [f64; 2],
+    points: &[StrokePoint],
+    radii: Option<&[f64]>,
+) -> Option<StrokeHit> {
+    let first = points.first()?;
+
+    let mut nearest = StrokeHit {
+        index: 0,
+        running_length: first.running_length,
+        distance: dist(point, first.point) - radii.map_or(0.0, |radii| radii[0]),
+    };
+
+    for (i, pair) in points.windows(2).enumerate() {
+        let (a, b) = (&pair[0], &pair[1]);
+        let t = nearest_t(point, a.point, b.point);
+        let radius = radii.map_or(0.0, |radii| radii[i] + (radii[i + 1] - radii[i]) * t);
+        let distance = dist(point, lrp(a.point, b.point, t)) - radius;
+
+        if distance < nearest.distance {
+            nearest = StrokeHit {
+                index: if t < 0.5 { i } else { i + 1 },
+                running_length: a.running_length + (b.running_length - a.running_length) * t,
+                distance,
+            };
+        }
+    }
+
+    Some(nearest)
+}
+
+/// The distance from a point to the nearest edge of a closed polygon.
+fn distance_to_edges(point: [f64; 2], polygon: &[[f64; 2]]) -> Option<f64> {
+    let count = polygon.len();
+    (0..count)
+        .map(|i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % count]);
+            dist(point, lrp(a, b, nearest_t(point, a, b)))
+        })
+        .reduce(f64::min)
+}
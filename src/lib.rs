@@ -1,14 +1,54 @@
+// `map_or(true, ..)` stands in for `Option::is_none_or`, which needs Rust 1.82
+#![allow(clippy::unnecessary_map_or)]
+
+mod erase_stroke;
+mod find_end_hook;
+mod get_closed_stroke;
 mod get_stroke;
+mod get_stroke_mesh;
+mod get_stroke_metrics;
 mod get_stroke_outline_points;
 mod get_stroke_points;
 mod get_stroke_radius;
+mod hit_test;
+mod path;
+mod polygon;
+mod predict_points;
+mod resample_points;
+mod select_with_lasso;
+mod simplify;
+mod smoother;
+mod stroke_builder;
+mod stroke_collection;
+mod svg;
+mod transform;
 mod types;
 mod utils;
+mod variable_width_path;
 mod vec;
 
+pub use erase_stroke::erase_stroke;
+pub use find_end_hook::find_end_hook;
+pub use get_closed_stroke::get_closed_stroke;
 pub use get_stroke::get_stroke;
-pub use get_stroke_outline_points::get_stroke_outline_points;
+pub use get_stroke_mesh::get_stroke_mesh;
+pub use get_stroke_metrics::get_stroke_metrics;
+pub use get_stroke_outline_points::{get_stroke_outline, get_stroke_outline_points};
 pub use get_stroke_points::get_stroke_points;
 pub use get_stroke_radius::get_stroke_radius;
+pub use hit_test::{contains_point, distance_to_stroke};
+pub use path::{draw_stroke, PathSink};
+pub use predict_points::predict_points;
+pub use resample_points::resample_points;
+pub use select_with_lasso::select_with_lasso;
+pub use simplify::{simplify_outline, simplify_points};
+pub use smoother::{ExponentialSmoother, KalmanFilter, LazyBrush, OneEuroFilter, Smoother};
+pub use stroke_builder::{StrokeBuilder, StrokeDelta};
+pub use stroke_collection::{StrokeCollection, StrokeId};
+pub use svg::{SvgDocument, SvgElement, SvgGroup, SvgPath, SvgStroke};
+pub use transform::Transform;
 pub use types::*;
-pub use utils::get_svg_path_from_stroke; 
\ No newline at end of file
+pub use utils::{
+    get_polygons_from_stroke, get_svg_path_from_stroke, get_svg_path_from_stroke_with_options,
+};
+pub use variable_width_path::{PathKnot, VariableWidthPath}; 
\ No newline at end of file
@@ -0,0 +1,52 @@
+use crate::vec::med;
+
+/// Something that can draw a path, such as a 2D canvas or a scene graph.
+///
+/// `draw_stroke` sends a stroke's outline to a sink as a series of path
+/// commands, so it can be drawn without going through SVG. `SvgPath`
+/// is the sink that writes SVG path data.
+pub trait PathSink {
+    /// Start a new subpath at `point`.
+    fn move_to(&mut self, point: [f64; 2]);
+
+    /// Draw a straight line to `point`.
+    fn line_to(&mut self, point: [f64; 2]);
+
+    /// Draw a quadratic Bézier curve to `point`.
+    fn quad_to(&mut self, control: [f64; 2], point: [f64; 2]);
+
+    /// Draw a cubic Bézier curve to `point`.
+    fn cubic_to(&mut self, control1: [f64; 2], control2: [f64; 2], point: [f64; 2]);
+
+    /// Close the current subpath with a straight line back to its start.
+    fn close(&mut self);
+}
+
+/// Draws a stroke's outline to a path sink as a smooth curve.
+///
+/// The curve passes through the midpoints between the outline points, with
+/// the points themselves as the control points of quadratic curves. This is
+/// the same curve that `get_svg_path_from_stroke` writes.
+///
+/// # Arguments
+/// * `points` - The outline points returned by `get_stroke`
+/// * `closed` - Whether to close the path
+/// * `sink` - The path sink to draw to
+pub fn draw_stroke<S: PathSink + ?Sized>(points: &[[f64; 2]], closed: bool, sink: &mut S) {
+    let len = points.len();
+
+    if len < 4 {
+        return;
+    }
+
+    sink.move_to(points[0]);
+    sink.quad_to(points[1], med(points[1], points[2]));
+
+    for i in 2..(len - 1) {
+        sink.quad_to(points[i], med(points[i], points[i + 1]));
+    }
+
+    if closed {
+        sink.close();
+    }
+}
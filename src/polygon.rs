@@ -0,0 +1,372 @@
+use crate::types::Polygon;
+use crate::vec::{add, cpr, dist2, dpr, is_equal, len, mul, per, sub};
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::TAU;
+
+// How close an intersection can be to the end of an edge before it's
+// treated as being at the end, as a fraction of the edge's length
+const SNAP: f64 = 1e-10;
+
+/// The signed area of a polygon, positive when its points run counter-clockwise
+/// (with the y axis pointing up).
+pub(crate) fn signed_area(points: &[[f64; 2]]) -> f64 {
+    if points.len() < 3 {
+        return 0.0;
+    }
+
+    let mut area = 0.0;
+    for (i, a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        area += a[0] * b[1] - b[0] * a[1];
+    }
+
+    area / 2.0
+}
+
+/// The centroid of a polygon multiplied by its signed area, so that the
+/// moments of several polygons can be added up.
+pub(crate) fn area_moment(points: &[[f64; 2]]) -> [f64; 2] {
+    let mut moment = [0.0, 0.0];
+    for (i, &a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        let cross = cpr(a, b);
+        moment = add(moment, mul(add(a, b), cross / 6.0));
+    }
+
+    moment
+}
+
+/// The convex hull of some points, running counter-clockwise (with the y
+/// axis pointing up) without repeating its first point.
+pub(crate) fn convex_hull(points: &[[f64; 2]]) -> Vec<[f64; 2]> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a[0].total_cmp(&b[0]).then(a[1].total_cmp(&b[1])));
+    sorted.dedup();
+
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    // Andrew's monotone chain: the lower half, then the upper half
+    let reversed: Vec<[f64; 2]> = sorted.iter().rev().copied().collect();
+    let mut hull: Vec<[f64; 2]> = Vec::with_capacity(sorted.len() + 1);
+    for pass in [&sorted, &reversed] {
+        let start = hull.len();
+        for &point in pass.iter() {
+            while hull.len() >= start + 2 {
+                let (a, b) = (hull[hull.len() - 2], hull[hull.len() - 1]);
+                if cpr(sub(b, a), sub(point, a)) > 0.0 {
+                    break;
+                }
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // The last point of each half is the first of the next
+        hull.pop();
+    }
+
+    hull
+}
+
+/// The number of times a polygon winds around a point, counting
+/// counter-clockwise turns as positive.
+pub(crate) fn winding_number(point: [f64; 2], polygon: &[[f64; 2]]) -> i32 {
+    let mut winding = 0;
+
+    for (i, &a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        let side = cpr(sub(b, a), sub(point, a));
+
+        if a[1] <= point[1] {
+            if b[1] > point[1] && side > 0.0 {
+                winding += 1;
+            }
+        } else if b[1] <= point[1] && side < 0.0 {
+            winding -= 1;
+        }
+    }
+
+    winding
+}
+
+/// Split a polygon that may cross over itself into simple polygons that
+/// cover the same area under the nonzero fill rule. Outer rings run
+/// counter-clockwise and holes clockwise (with the y axis pointing up).
+pub(crate) fn resolve_overlaps(points: &[[f64; 2]]) -> Vec<Polygon> {
+    // Points a rounding error apart would give edges with no direction to
+    // tell their sides apart by, so merge them
+    let size = points.iter().fold(0.0, |size: f64, p| size.max(p[0].abs()).max(p[1].abs()));
+    let tolerance = size * 1e-12;
+    let mut ring: Vec<[f64; 2]> = Vec::with_capacity(points.len());
+    for &point in points {
+        if ring.last().map_or(true, |&last| dist2(last, point) > tolerance * tolerance) {
+            ring.push(point);
+        }
+    }
+    while ring.len() > 1 && dist2(ring[0], ring[ring.len() - 1]) <= tolerance * tolerance {
+        ring.pop();
+    }
+
+    if ring.len() < 3 {
+        return Vec::new();
+    }
+
+    let mut graph = Graph::default();
+    for (a, b) in split_edges(&ring) {
+        // Keep the edges with filled space on one side and not the other,
+        // turned so that the filled side is on the left. The sides are tested
+        // just off the middle of the edge, which no other edge crosses.
+        let normal = mul(per(sub(b, a)), 1e-6);
+        let mid = [(a[0] + b[0]) / 2.0, (a[1] + b[1]) / 2.0];
+        let is_left_filled = winding_number(sub(mid, normal), &ring) != 0;
+        let is_right_filled = winding_number(add(mid, normal), &ring) != 0;
+
+        match (is_left_filled, is_right_filled) {
+            (true, false) => graph.add_edge(a, b),
+            (false, true) => graph.add_edge(b, a),
+            _ => {}
+        }
+    }
+
+    let rings = graph.rings();
+    let scale = rings.iter().map(|r| signed_area(r).abs()).fold(0.0, f64::max);
+
+    let mut polygons: Vec<Polygon> = Vec::new();
+    let mut holes: Vec<Vec<[f64; 2]>> = Vec::new();
+    for ring in rings {
+        let area = signed_area(&ring);
+        if area.abs() <= scale * 1e-12 {
+            continue;
+        }
+
+        if area > 0.0 {
+            polygons.push(Polygon {
+                outer: ring,
+                holes: Vec::new(),
+            });
+        } else {
+            holes.push(ring);
+        }
+    }
+
+    // Each hole belongs to the smallest outer ring around it
+    for hole in holes {
+        let inside = |polygon: &&mut Polygon| {
+            hole.iter()
+                .zip(hole.iter().skip(1))
+                .map(|(&a, &b)| mul(add(a, b), 0.5))
+                .any(|point| winding_number(point, &polygon.outer) != 0)
+        };
+
+        let owner = polygons
+            .iter_mut()
+            .filter(|polygon| inside(polygon))
+            .min_by(|a, b| signed_area(&a.outer).total_cmp(&signed_area(&b.outer)));
+
+        if let Some(polygon) = owner {
+            polygon.holes.push(hole);
+        }
+    }
+
+    polygons
+}
+
+/// Split a polygon's edges wherever they cross each other.
+fn split_edges(ring: &[[f64; 2]]) -> Vec<([f64; 2], [f64; 2])> {
+    let count = ring.len();
+    let edge = |i: usize| (ring[i], ring[(i + 1) % count]);
+
+    // The points where each edge is crossed, by how far along the edge they are
+    let mut splits: Vec<Vec<(f64, [f64; 2])>> = vec![Vec::new(); count];
+
+    // Check each edge against the edges that overlap it from left to right
+    let min_x = |i: usize| f64::min(edge(i).0[0], edge(i).1[0]);
+    let max_x = |i: usize| f64::max(edge(i).0[0], edge(i).1[0]);
+    let mut order: Vec<usize> = (0..count).collect();
+    order.sort_by(|&i, &j| min_x(i).total_cmp(&min_x(j)));
+
+    for (k, &i) in order.iter().enumerate() {
+        for &j in &order[k + 1..] {
+            if min_x(j) > max_x(i) {
+                break;
+            }
+
+            let (a0, a1) = edge(i);
+            let (b0, b1) = edge(j);
+            let Some((t, u, point)) = crossing(a0, a1, b0, b1) else {
+                continue;
+            };
+
+            if t > 0.0 && t < 1.0 {
+                splits[i].push((t, point));
+            }
+            if u > 0.0 && u < 1.0 {
+                splits[j].push((u, point));
+            }
+        }
+    }
+
+    let mut edges = Vec::new();
+    for (i, mut points) in splits.into_iter().enumerate() {
+        let (a, b) = edge(i);
+        points.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+        let mut prev = a;
+        for point in points.into_iter().map(|(_, p)| p).chain(std::iter::once(b)) {
+            if !is_equal(prev, point) {
+                edges.push((prev, point));
+                prev = point;
+            }
+        }
+    }
+
+    edges
+}
+
+/// Where two edges cross, as how far along each edge the crossing is and the
+/// crossing point. Crossings close to the end of an edge are moved to the end.
+fn crossing(
+    a0: [f64; 2],
+    a1: [f64; 2],
+    b0: [f64; 2],
+    b1: [f64; 2],
+) -> Option<(f64, f64, [f64; 2])> {
+    let da = sub(a1, a0);
+    let db = sub(b1, b0);
+    let denominator = cpr(da, db);
+
+    // Parallel edges are left as they are
+    if denominator.abs() <= f64::EPSILON * len(da) * len(db) {
+        return None;
+    }
+
+    let snap = |t: f64| {
+        if t.abs() < SNAP {
+            0.0
+        } else if (t - 1.0).abs() < SNAP {
+            1.0
+        } else {
+            t
+        }
+    };
+
+    let t = snap(cpr(sub(b0, a0), db) / denominator);
+    let u = snap(cpr(sub(b0, a0), da) / denominator);
+
+    if !(0.0..=1.0).contains(&t) || !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let point = if t == 0.0 {
+        a0
+    } else if t == 1.0 {
+        a1
+    } else if u == 0.0 {
+        b0
+    } else if u == 1.0 {
+        b1
+    } else {
+        add(a0, mul(da, t))
+    };
+
+    Some((t, u, point))
+}
+
+/// The boundary edges between filled and empty space, with the filled
+/// space on the left of each edge.
+#[derive(Default)]
+struct Graph {
+    points: Vec<[f64; 2]>,
+    ids: HashMap<[u64; 2], usize>,
+    edges: Vec<(usize, usize)>,
+    seen: HashSet<(usize, usize)>,
+}
+
+impl Graph {
+    fn id(&mut self, point: [f64; 2]) -> usize {
+        // Treat 0 and -0 as the same point
+        let key = [(point[0] + 0.0).to_bits(), (point[1] + 0.0).to_bits()];
+        *self.ids.entry(key).or_insert_with(|| {
+            self.points.push(point);
+            self.points.len() - 1
+        })
+    }
+
+    fn add_edge(&mut self, a: [f64; 2], b: [f64; 2]) {
+        let edge = (self.id(a), self.id(b));
+
+        // Edges that lie on top of each other only count once
+        if self.seen.insert(edge) {
+            self.edges.push(edge);
+        }
+    }
+
+    /// Follow the edges around into closed rings. Where rings touch at a
+    /// point, each ring turns as far left as it can, so that it stays simple.
+    fn rings(&self) -> Vec<Vec<[f64; 2]>> {
+        let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (i, &(from, _)) in self.edges.iter().enumerate() {
+            outgoing.entry(from).or_default().push(i);
+        }
+
+        let direction = |edge: usize| {
+            let (from, to) = self.edges[edge];
+            sub(self.points[to], self.points[from])
+        };
+
+        let mut used = vec![false; self.edges.len()];
+        let mut rings = Vec::new();
+
+        for start in 0..self.edges.len() {
+            if used[start] {
+                continue;
+            }
+            used[start] = true;
+
+            let mut ring = vec![self.points[self.edges[start].0]];
+            let mut current = start;
+
+            loop {
+                let (from, to) = self.edges[current];
+                let back = sub(self.points[from], self.points[to]);
+
+                // The next edge clockwise from the way back
+                let turn = |edge: usize| {
+                    let d = direction(edge);
+                    let angle = -f64::atan2(cpr(back, d), dpr(back, d));
+                    if angle <= 0.0 {
+                        angle + TAU
+                    } else {
+                        angle
+                    }
+                };
+
+                let next = outgoing
+                    .get(&to)
+                    .into_iter()
+                    .flatten()
+                    .copied()
+                    .filter(|&edge| !used[edge] || edge == start)
+                    .min_by(|&a, &b| turn(a).total_cmp(&turn(b)));
+
+                match next {
+                    Some(edge) if edge == start => {
+                        ring.push(ring[0]);
+                        rings.push(ring);
+                        break;
+                    }
+                    Some(edge) => {
+                        used[edge] = true;
+                        ring.push(self.points[to]);
+                        current = edge;
+                    }
+                    // The edges don't meet up, which floating point errors can cause
+                    None => break,
+                }
+            }
+        }
+
+        rings
+    }
+}
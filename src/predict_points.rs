@@ -0,0 +1,109 @@
+use crate::get_stroke_outline_points::FRAME_DURATION;
+use crate::smoother::elapsed;
+use crate::types::{InputPoint, PredictionModel, PredictionOptions};
+use crate::vec::{add, mul, sub};
+
+/// Guess where the pen is going from the latest input points, to draw the
+/// stroke ahead of the pen and hide the time it takes a point to reach the
+/// screen.
+///
+/// The predicted points carry on from the last input point, with its
+/// pressure and pen orientation, and with timestamps after its own. Draw them
+/// with `StrokeBuilder::predict`, which throws them away when the next input
+/// point arrives.
+///
+/// # Arguments
+/// * `points` - The input points so far, of which only the latest few are used
+/// * `options` - How to predict the points, and how far ahead
+///
+/// # Returns
+/// The predicted points, or none if there are fewer than two input points
+pub fn predict_points(points: &[InputPoint], options: &PredictionOptions) -> Vec<InputPoint> {
+    let latency = options.latency.unwrap_or(2.0 * FRAME_DURATION);
+    let count = options.count.unwrap_or(3);
+
+    let Some(last) = points.last() else {
+        return Vec::new();
+    };
+
+    if points.len() < 2 || count == 0 || latency.is_nan() || latency <= 0.0 {
+        return Vec::new();
+    }
+
+    // How many points before the last one to fit, and whether to fit the
+    // pen's acceleration as well as its velocity
+    let (history, degree) = match options.model.unwrap_or_default() {
+        PredictionModel::ConstantVelocity => (1, 1),
+        PredictionModel::ConstantAcceleration => (2, 2),
+        PredictionModel::CurveFit { history } => (history.max(1), 2),
+    };
+
+    // The times of the points before the last one, and their offsets from it
+    let start = points.len().saturating_sub(history + 1);
+    let mut time = 0.0;
+    let samples: Vec<(f64, [f64; 2])> = points[start..]
+        .windows(2)
+        .rev()
+        .map(|pair| {
+            time -= elapsed(pair[0].timestamp(), pair[1].timestamp());
+            (time, sub(pair[0].point(), last.point()))
+        })
+        .collect();
+
+    let [velocity, acceleration] = fit(&samples, degree.min(samples.len()));
+
+    (1..=count)
+        .map(|i| {
+            let dt = latency * i as f64 / count as f64;
+            let point = add(
+                last.point(),
+                add(mul(velocity, dt), mul(acceleration, dt * dt)),
+            );
+
+            match *last {
+                InputPoint::Array(_, pressure) => InputPoint::Array(point, pressure),
+                InputPoint::Struct {
+                    pressure,
+                    timestamp,
+                    orientation,
+                    ..
+                } => InputPoint::Struct {
+                    x: point[0],
+                    y: point[1],
+                    pressure,
+                    timestamp: timestamp.map(|timestamp| timestamp + dt),
+                    orientation,
+                },
+            }
+        })
+        .collect()
+}
+
+/// Fit `velocity * t + acceleration * t²` to the offsets of earlier points
+/// from the last point, by least squares, so that the curve passes through
+/// the last point at `t = 0`. With a `degree` of 1, or when the times can't
+/// tell the two apart, the acceleration is left at 0.
+fn fit(samples: &[(f64, [f64; 2])], degree: usize) -> [[f64; 2]; 2] {
+    let (mut s2, mut s3, mut s4) = (0.0, 0.0, 0.0);
+    let (mut b1, mut b2) = ([0.0, 0.0], [0.0, 0.0]);
+    for &(t, offset) in samples {
+        s2 += t * t;
+        s3 += t * t * t;
+        s4 += t * t * t * t;
+        b1 = add(b1, mul(offset, t));
+        b2 = add(b2, mul(offset, t * t));
+    }
+
+    let det = s2 * s4 - s3 * s3;
+    if degree >= 2 && det.abs() > 1e-9 * s2 * s4 {
+        let velocity = mul(sub(mul(b1, s4), mul(b2, s3)), 1.0 / det);
+        let acceleration = mul(sub(mul(b2, s2), mul(b1, s3)), 1.0 / det);
+        return [velocity, acceleration];
+    }
+
+    if s2 > 0.0 {
+        [mul(b1, 1.0 / s2), [0.0, 0.0]]
+    } else {
+        [[0.0, 0.0], [0.0, 0.0]]
+    }
+}
@@ -0,0 +1,181 @@
+use crate::types::{InputPoint, Resampling};
+use crate::vec::{cpr, dist, dpr, is_equal, lrp, sub};
+
+/// Space out input points evenly along the line through them, or more
+/// closely where it bends, so that a stroke looks the same however often the
+/// device samples the pen.
+///
+/// The first and last points are kept, and the pressure and timestamp of each
+/// new point are interpolated between the input points on either side of it.
+/// Each new point takes the pen orientation of the nearer input point.
+///
+/// # Arguments
+/// * `points` - The input points
+/// * `resampling` - How far apart to put the new points
+///
+/// # Returns
+/// The new input points, or the input points as they are if the spacing
+/// isn't greater than 0
+pub fn resample_points(points: &[InputPoint], resampling: &Resampling) -> Vec<InputPoint> {
+    let Some(first) = points.first() else {
+        return Vec::new();
+    };
+
+    let mut resampler = Resampler::new(*resampling, first);
+    let mut resampled = vec![first.clone()];
+    for point in &points[1..] {
+        resampler.push(point, &mut resampled);
+    }
+    resampler.finish(&mut resampled);
+
+    resampled
+}
+
+/// The state carried between input points while resampling them.
+///
+/// The points along each part of the line are only placed once the input
+/// point after it is known, as that sets how sharply the line bends at its
+/// end. `StrokeBuilder` keeps a resampler around so that the points placed
+/// so far don't change as new input points are added.
+#[derive(Debug, Clone)]
+pub(crate) struct Resampler {
+    resampling: Resampling,
+    // The input point before `start`, if there is one
+    before: Option<[f64; 2]>,
+    // The input points at the start and end of the part of the line that's
+    // still to be resampled
+    start: InputPoint,
+    end: Option<InputPoint>,
+    // How far along the line the last point was placed before `start`
+    carry: f64,
+}
+
+impl Resampler {
+    /// Start at the first input point, which is kept as it is.
+    pub(crate) fn new(resampling: Resampling, first: &InputPoint) -> Self {
+        Self {
+            resampling,
+            before: None,
+            start: first.clone(),
+            end: None,
+            carry: 0.0,
+        }
+    }
+
+    /// Add the next input point, pushing the points that can now be placed
+    /// to `resampled`.
+    pub(crate) fn push(&mut self, point: &InputPoint, resampled: &mut Vec<InputPoint>) {
+        let latest = self.end.as_ref().unwrap_or(&self.start);
+        if is_equal(latest.point(), point.point()) {
+            return;
+        }
+
+        if let Some(end) = self.end.take() {
+            self.walk(&end, Some(point.point()), resampled);
+            self.before = Some(self.start.point());
+            self.start = end;
+        }
+        self.end = Some(point.clone());
+    }
+
+    /// Push the points along the rest of the line, and the last input point.
+    pub(crate) fn finish(mut self, resampled: &mut Vec<InputPoint>) {
+        if let Some(end) = self.end.take() {
+            self.walk(&end, None, resampled);
+            if self.carry > 0.0 {
+                resampled.push(end);
+            }
+        }
+    }
+
+    /// Place points along the line from `start` to `end`, where the line
+    /// turns toward `next`.
+    fn walk(&mut self, end: &InputPoint, next: Option<[f64; 2]>, resampled: &mut Vec<InputPoint>) {
+        let (a, b) = (self.start.point(), end.point());
+        let length = dist(a, b);
+        let spacing = self.spacing(a, b, next);
+
+        // Without a spacing to keep to, keep the input points
+        if spacing.is_nan() || spacing <= 0.0 {
+            resampled.push(end.clone());
+            self.carry = 0.0;
+            return;
+        }
+
+        let mut last = None;
+        let mut along = f64::max(0.0, spacing - self.carry);
+        while along <= length {
+            resampled.push(interpolate(&self.start, end, along / length));
+            last = Some(along);
+            along += spacing;
+        }
+
+        self.carry = match last {
+            Some(last) => length - last,
+            None => self.carry + length,
+        };
+    }
+
+    /// The spacing between points on the line from `a` to `b`.
+    fn spacing(&self, a: [f64; 2], b: [f64; 2], next: Option<[f64; 2]>) -> f64 {
+        match self.resampling {
+            Resampling::Uniform { spacing } => spacing,
+            Resampling::Adaptive {
+                tolerance,
+                max_spacing,
+            } => {
+                if tolerance <= 0.0 {
+                    return max_spacing;
+                }
+
+                // A chord `s` long across an arc of radius `r` strays about
+                // s² / 8r from it
+                let curvature = f64::max(
+                    self.before.map_or(0.0, |before| curvature(before, a, b)),
+                    next.map_or(0.0, |next| curvature(a, b, next)),
+                );
+                let spacing = (8.0 * tolerance / curvature).sqrt();
+                spacing.clamp(tolerance.min(max_spacing), max_spacing)
+            }
+        }
+    }
+}
+
+/// How sharply the line from `a` through `b` to `c` bends at `b`, as the
+/// angle it turns through over the length around `b`.
+fn curvature(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    let (ab, bc) = (sub(b, a), sub(c, b));
+    let length = (dist(a, b) + dist(b, c)) / 2.0;
+    if length == 0.0 {
+        return 0.0;
+    }
+
+    cpr(ab, bc).abs().atan2(dpr(ab, bc)) / length
+}
+
+/// The input point a share `t` of the way from `a` to `b`.
+fn interpolate(a: &InputPoint, b: &InputPoint, t: f64) -> InputPoint {
+    let lerp = |a: Option<f64>, b: Option<f64>| match (a, b) {
+        (Some(a), Some(b)) => Some(a + (b - a) * t),
+        _ if t < 0.5 => a.or(b),
+        _ => b.or(a),
+    };
+
+    let point = lrp(a.point(), b.point(), t);
+    let pressure = lerp(a.pressure(), b.pressure());
+
+    match (a, b) {
+        (InputPoint::Array(..), InputPoint::Array(..)) => InputPoint::Array(point, pressure),
+        _ => InputPoint::Struct {
+            x: point[0],
+            y: point[1],
+            pressure,
+            timestamp: lerp(a.timestamp(), b.timestamp()),
+            orientation: if t < 0.5 {
+                a.orientation()
+            } else {
+                b.orientation()
+            },
+        },
+    }
+}
@@ -0,0 +1,130 @@
+use crate::polygon::winding_number;
+use crate::types::{Bounds, Containment, LassoSelection, StrokeShape};
+use crate::vec::{cpr, dist, lrp, sub};
+
+/// Find which strokes are inside a lasso.
+///
+/// A stroke's coverage is the share of its length inside the lasso, measured
+/// along its outline or along its centerline (ignoring the stroke's width),
+/// depending on the shape it's given as. The lasso is closed from its last
+/// point back to its first, and where it crosses itself the nonzero rule
+/// decides what's inside. A lasso drawn inside an outline selects part of
+/// the stroke, though none of the outline is inside it.
+///
+/// # Arguments
+/// * `lasso` - The points of the lasso, such as the input points of a lasso stroke
+/// * `strokes` - The strokes to test
+/// * `threshold` - The coverage, from 0 to 1, at which a stroke counts as
+///   inside rather than partly inside. Use 1 for strokes wholly inside the lasso
+///
+/// # Returns
+/// Where each stroke is, in the same order as `strokes`
+pub fn select_with_lasso(
+    lasso: &[[f64; 2]],
+    strokes: &[StrokeShape],
+    threshold: f64,
+) -> Vec<LassoSelection> {
+    let lasso_bounds = Bounds::from_points(lasso);
+
+    strokes
+        .iter()
+        .map(|stroke| {
+            let (line, is_closed) = match *stroke {
+                StrokeShape::Outline { outline, .. } => (outline.to_vec(), true),
+                StrokeShape::Centerline { points, .. } => {
+                    (points.iter().map(|p| p.point).collect(), false)
+                }
+            };
+
+            let overlaps = match (lasso_bounds, Bounds::from_points(&line)) {
+                (Some(a), Some(b)) => lasso.len() >= 3 && a.intersects(&b),
+                _ => false,
+            };
+            let coverage = if overlaps {
+                coverage(&line, is_closed, lasso)
+            } else {
+                0.0
+            };
+
+            // A lasso drawn wholly inside a thick stroke crosses none of its
+            // outline, but still takes in some of the stroke
+            let is_lasso_inside =
+                is_closed && overlaps && coverage == 0.0 && winding_number(lasso[0], &line) != 0;
+
+            let containment = if coverage > 0.0 && coverage >= threshold {
+                Containment::Inside
+            } else if coverage > 0.0 || is_lasso_inside {
+                Containment::Partial
+            } else {
+                Containment::Outside
+            };
+
+            LassoSelection {
+                containment,
+                coverage,
+            }
+        })
+        .collect()
+}
+
+/// The share of a line's length that's inside a polygon.
+fn coverage(line: &[[f64; 2]], is_closed: bool, polygon: &[[f64; 2]]) -> f64 {
+    let segments = line.windows(2).map(|pair| (pair[0], pair[1]));
+    let closing = match (line.first(), line.last()) {
+        (Some(&first), Some(&last)) if is_closed && first != last => Some((last, first)),
+        _ => None,
+    };
+
+    let mut inside = 0.0;
+    let mut total = 0.0;
+    for (a, b) in segments.chain(closing) {
+        let length = dist(a, b);
+        if length == 0.0 {
+            continue;
+        }
+        total += length;
+
+        // Split the segment where it crosses the polygon's edges, and test
+        // the middle of each part
+        let mut cuts = vec![0.0, 1.0];
+        for (i, &c) in polygon.iter().enumerate() {
+            let d = polygon[(i + 1) % polygon.len()];
+            if let Some(t) = crossing(a, b, c, d) {
+                cuts.push(t);
+            }
+        }
+        cuts.sort_by(f64::total_cmp);
+
+        for pair in cuts.windows(2) {
+            let (t0, t1) = (pair[0], pair[1]);
+            if t1 > t0 && winding_number(lrp(a, b, (t0 + t1) / 2.0), polygon) != 0 {
+                inside += (t1 - t0) * length;
+            }
+        }
+    }
+
+    if total > 0.0 {
+        inside / total
+    } else {
+        // A dot is inside or it isn't
+        match line.first() {
+            Some(&point) if winding_number(point, polygon) != 0 => 1.0,
+            _ => 0.0,
+        }
+    }
+}
+
+/// How far along the segment from `a` to `b` it crosses the segment from `c`
+/// to `d`, if it does.
+fn crossing(a: [f64; 2], b: [f64; 2], c: [f64; 2], d: [f64; 2]) -> Option<f64> {
+    let r = sub(b, a);
+    let s = sub(d, c);
+    let denominator = cpr(r, s);
+    if denominator == 0.0 {
+        return None;
+    }
+
+    let t = cpr(sub(c, a), s) / denominator;
+    let u = cpr(sub(c, a), r) / denominator;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then_some(t)
+}
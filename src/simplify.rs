@@ -0,0 +1,237 @@
+use crate::get_stroke_radius::get_stroke_radius;
+use crate::types::{InputPoint, Simplification, StrokeOptions};
+use crate::vec::{cpr, dist, dpr, is_equal, len2, lrp, sub};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+/// Drop the input points that a stroke doesn't need, to store or send it in
+/// less space. The points that are kept are unchanged, with their pressure,
+/// timestamp and pen orientation.
+///
+/// Every dropped point is within `tolerance` of the line between the points
+/// kept either side of it, counting the change in the stroke's width there
+/// when the width follows the pen's pressure. So the simplified stroke is
+/// drawn within about `tolerance` of the original, as long as nothing else
+/// depends on how many points there are: streamlining moves each point part
+/// of the way from the one before, so draw the stroke with `resampling` set
+/// or without streamlining, and simulated pressure comes from the spacing of
+/// the points, so store the pen's pressure or draw without simulating it.
+///
+/// # Arguments
+/// * `points` - The stroke's input points
+/// * `options` - The options the stroke is drawn with
+/// * `simplification` - How to choose the points to drop, and how far the
+///   simplified stroke may stray
+///
+/// # Returns
+/// The points that are kept, including the first and last, or the input
+/// points as they are if the tolerance is less than 0
+pub fn simplify_points(
+    points: &[InputPoint],
+    options: &StrokeOptions,
+    simplification: &Simplification,
+) -> Vec<InputPoint> {
+    let size = options.size.unwrap_or(16.0);
+    let thinning = options.thinning.unwrap_or(0.5);
+    let follows_pressure = thinning != 0.0 && !options.simulate_pressure.unwrap_or(true);
+
+    let positions: Vec<[f64; 2]> = points.iter().map(InputPoint::point).collect();
+    let radii: Vec<f64> = points
+        .iter()
+        .map(|point| {
+            if follows_pressure {
+                let pressure = point.pressure().unwrap_or(0.5);
+                get_stroke_radius(size, thinning, pressure, options.easing)
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    let kept = simplify(&positions, &radii, simplification);
+    points
+        .iter()
+        .zip(kept)
+        .filter(|(_, is_kept)| *is_kept)
+        .map(|(point, _)| point.clone())
+        .collect()
+}
+
+/// Drop the points of an outline polygon, such as one from `get_stroke`,
+/// that its shape doesn't need. Every dropped point is within `tolerance` of
+/// the simplified outline.
+///
+/// The outline is taken to be closed, whether or not its last point repeats
+/// its first, and it's returned the same way.
+pub fn simplify_outline(outline: &[[f64; 2]], simplification: &Simplification) -> Vec<[f64; 2]> {
+    let is_repeated = outline.len() > 1 && is_equal(outline[0], outline[outline.len() - 1]);
+    let ring = if is_repeated {
+        &outline[..outline.len() - 1]
+    } else {
+        outline
+    };
+
+    if ring.len() < 4 {
+        return outline.to_vec();
+    }
+
+    // Split the ring at the point furthest from its first point, which are
+    // sure to be kept, and simplify each half as a line
+    let far = (1..ring.len())
+        .max_by(|&a, &b| dist(ring[0], ring[a]).total_cmp(&dist(ring[0], ring[b])))
+        .unwrap_or(1);
+
+    let mut looped = ring.to_vec();
+    looped.push(ring[0]);
+    let radii = vec![0.0; far + 1];
+    let mut kept = simplify(&looped[..=far], &radii, simplification);
+    let radii = vec![0.0; looped.len() - far];
+    kept.extend(simplify(&looped[far..], &radii, simplification).into_iter().skip(1));
+
+    let mut simplified: Vec<[f64; 2]> = looped
+        .into_iter()
+        .zip(kept)
+        .filter(|(_, is_kept)| *is_kept)
+        .map(|(point, _)| point)
+        .collect();
+
+    // The first point was added to the end to close the loop
+    if !is_repeated {
+        simplified.pop();
+    }
+
+    simplified
+}
+
+/// Which of the points along a line to keep, with the radius of the stroke
+/// at each point.
+fn simplify(points: &[[f64; 2]], radii: &[f64], simplification: &Simplification) -> Vec<bool> {
+    let tolerance = match *simplification {
+        Simplification::RamerDouglasPeucker { tolerance } => tolerance,
+        Simplification::VisvalingamWhyatt { tolerance } => tolerance,
+    };
+
+    if points.len() < 3 || tolerance.is_nan() || tolerance < 0.0 {
+        return vec![true; points.len()];
+    }
+
+    match simplification {
+        Simplification::RamerDouglasPeucker { .. } => {
+            ramer_douglas_peucker(points, radii, tolerance)
+        }
+        Simplification::VisvalingamWhyatt { .. } => visvalingam_whyatt(points, radii, tolerance),
+    }
+}
+
+fn ramer_douglas_peucker(points: &[[f64; 2]], radii: &[f64], tolerance: f64) -> Vec<bool> {
+    let mut kept = vec![false; points.len()];
+    kept[0] = true;
+    kept[points.len() - 1] = true;
+
+    let mut spans = vec![(0, points.len() - 1)];
+    while let Some((a, b)) = spans.pop() {
+        let furthest = (a + 1..b)
+            .map(|i| (i, error(points, radii, a, b, i)))
+            .max_by(|(_, x), (_, y)| x.total_cmp(y));
+
+        if let Some((i, error)) = furthest {
+            if error > tolerance {
+                kept[i] = true;
+                spans.push((a, i));
+                spans.push((i, b));
+            }
+        }
+    }
+
+    kept
+}
+
+/// A point that could be dropped, as it was when it was queued.
+struct Candidate {
+    area: f64,
+    index: usize,
+    prev: usize,
+    next: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.area.total_cmp(&other.area).then(self.index.cmp(&other.index))
+    }
+}
+
+fn visvalingam_whyatt(points: &[[f64; 2]], radii: &[f64], tolerance: f64) -> Vec<bool> {
+    let last = points.len() - 1;
+    let mut kept = vec![true; points.len()];
+    let mut prev: Vec<usize> = (0..points.len()).map(|i| i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (0..points.len()).map(|i| (i + 1).min(last)).collect();
+
+    let candidate = |index: usize, prev: usize, next: usize| Candidate {
+        area: cpr(sub(points[index], points[prev]), sub(points[next], points[prev])).abs() / 2.0,
+        index,
+        prev,
+        next,
+    };
+
+    let mut queue: BinaryHeap<Reverse<Candidate>> =
+        (1..last).map(|i| Reverse(candidate(i, i - 1, i + 1))).collect();
+
+    while let Some(Reverse(point)) = queue.pop() {
+        let i = point.index;
+
+        // Skip points whose neighbours have changed since they were queued
+        if !kept[i] || prev[i] != point.prev || next[i] != point.next {
+            continue;
+        }
+
+        // Dropping the point mustn't take any of the points dropped around
+        // it, or itself, too far from the line
+        let (a, b) = (point.prev, point.next);
+        if (a + 1..b).any(|j| error(points, radii, a, b, j) > tolerance) {
+            continue;
+        }
+
+        kept[i] = false;
+        next[a] = b;
+        prev[b] = a;
+
+        if a > 0 {
+            queue.push(Reverse(candidate(a, prev[a], b)));
+        }
+        if b < last {
+            queue.push(Reverse(candidate(b, a, next[b])));
+        }
+    }
+
+    kept
+}
+
+/// How far the point at `i` strays from the line between the points at `a`
+/// and `b`, plus how far its radius strays from the radius along the line.
+fn error(points: &[[f64; 2]], radii: &[f64], a: usize, b: usize, i: usize) -> f64 {
+    let ab = sub(points[b], points[a]);
+    let length2 = len2(ab);
+    let t = if length2 == 0.0 {
+        0.0
+    } else {
+        (dpr(sub(points[i], points[a]), ab) / length2).clamp(0.0, 1.0)
+    };
+
+    let distance = dist(points[i], lrp(points[a], points[b], t));
+    let radius = radii[a] + (radii[b] - radii[a]) * t;
+    distance + (radii[i] - radius).abs()
+}
@@ -0,0 +1,276 @@
+use crate::get_stroke_outline_points::FRAME_DURATION;
+use crate::types::SmootherType;
+use crate::vec::{add, dist, lrp, mul, sub, uni};
+use std::f64::consts::TAU;
+
+/// A filter that smooths the input points of a stroke before its stroke
+/// points are made, set with the `smoother` option.
+///
+/// A smoother is started at the first input point, which is used as it is,
+/// and then given each of the points that follow in turn. Timestamps are in
+/// milliseconds, and are `None` for points without them.
+pub trait Smoother: std::fmt::Debug {
+    /// Start again at the first point of a stroke.
+    fn start(&mut self, point: [f64; 2], timestamp: Option<f64>);
+
+    /// The smoothed position of the next input point.
+    fn smooth(&mut self, point: [f64; 2], timestamp: Option<f64>) -> [f64; 2];
+
+    /// A copy of the smoother and its state, so that `StrokeBuilder` can try
+    /// out the newest point without keeping it.
+    fn clone_box(&self) -> Box<dyn Smoother>;
+}
+
+impl Clone for Box<dyn Smoother> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl SmootherType {
+    /// A new smoother of this type, or `None` for `Streamline`, which is
+    /// built into `get_stroke_points`.
+    pub(crate) fn build(&self) -> Option<Box<dyn Smoother>> {
+        match *self {
+            SmootherType::Streamline => None,
+            SmootherType::Exponential { time_constant } => {
+                Some(Box::new(ExponentialSmoother::new(time_constant)))
+            }
+            SmootherType::OneEuro { min_cutoff, beta } => {
+                Some(Box::new(OneEuroFilter::new(min_cutoff, beta)))
+            }
+            SmootherType::Kalman {
+                process_noise,
+                measurement_noise,
+            } => Some(Box::new(KalmanFilter::new(process_noise, measurement_noise))),
+            SmootherType::LazyBrush { radius } => Some(Box::new(LazyBrush::new(radius))),
+            SmootherType::Custom(build) => Some(build()),
+        }
+    }
+}
+
+/// The time between two points in milliseconds, or one frame if either has
+/// no timestamp or they're out of order.
+pub(crate) fn elapsed(prev: Option<f64>, curr: Option<f64>) -> f64 {
+    match (prev, curr) {
+        (Some(prev), Some(curr)) if curr > prev => curr - prev,
+        _ => FRAME_DURATION,
+    }
+}
+
+/// An exponential moving average that forgets older points over a time,
+/// rather than over a number of points, so it smooths as much whatever the
+/// pen's sample rate.
+#[derive(Debug, Clone)]
+pub struct ExponentialSmoother {
+    time_constant: f64,
+    prev: [f64; 2],
+    prev_timestamp: Option<f64>,
+}
+
+impl ExponentialSmoother {
+    /// A smoother that moves about two thirds of the way to the pen every
+    /// `time_constant` milliseconds.
+    pub fn new(time_constant: f64) -> Self {
+        Self {
+            time_constant: time_constant.max(0.0),
+            prev: [0.0, 0.0],
+            prev_timestamp: None,
+        }
+    }
+}
+
+impl Smoother for ExponentialSmoother {
+    fn start(&mut self, point: [f64; 2], timestamp: Option<f64>) {
+        self.prev = point;
+        self.prev_timestamp = timestamp;
+    }
+
+    fn smooth(&mut self, point: [f64; 2], timestamp: Option<f64>) -> [f64; 2] {
+        let elapsed = elapsed(self.prev_timestamp, timestamp);
+        let alpha = if self.time_constant > 0.0 {
+            1.0 - (-elapsed / self.time_constant).exp()
+        } else {
+            1.0
+        };
+
+        self.prev = lrp(self.prev, point, alpha);
+        self.prev_timestamp = timestamp.or(self.prev_timestamp);
+        self.prev
+    }
+
+    fn clone_box(&self) -> Box<dyn Smoother> {
+        Box::new(self.clone())
+    }
+}
+
+/// The One Euro filter (Casiez, Roussel and Vogel, 2012), which smooths
+/// heavily when the pen moves slowly and hardly at all when it moves fast,
+/// to hide jitter without adding lag.
+#[derive(Debug, Clone)]
+pub struct OneEuroFilter {
+    min_cutoff: f64,
+    beta: f64,
+    prev: [f64; 2],
+    // The smoothed speed, in units per second
+    velocity: [f64; 2],
+    prev_timestamp: Option<f64>,
+}
+
+impl OneEuroFilter {
+    // The cutoff frequency for smoothing the speed, in hertz
+    const DERIVATIVE_CUTOFF: f64 = 1.0;
+
+    /// A filter that smooths out changes faster than `min_cutoff` hertz when
+    /// the pen is still, and raises the cutoff by `beta` for every unit per
+    /// second the pen moves.
+    pub fn new(min_cutoff: f64, beta: f64) -> Self {
+        Self {
+            min_cutoff: min_cutoff.max(1e-6),
+            beta: beta.max(0.0),
+            prev: [0.0, 0.0],
+            velocity: [0.0, 0.0],
+            prev_timestamp: None,
+        }
+    }
+
+    /// How far to move toward a new value, for a cutoff frequency in hertz
+    /// and a time step in seconds.
+    fn alpha(cutoff: f64, elapsed: f64) -> f64 {
+        let tau = 1.0 / (TAU * cutoff);
+        1.0 / (1.0 + tau / elapsed)
+    }
+}
+
+impl Smoother for OneEuroFilter {
+    fn start(&mut self, point: [f64; 2], timestamp: Option<f64>) {
+        self.prev = point;
+        self.velocity = [0.0, 0.0];
+        self.prev_timestamp = timestamp;
+    }
+
+    fn smooth(&mut self, point: [f64; 2], timestamp: Option<f64>) -> [f64; 2] {
+        let elapsed = elapsed(self.prev_timestamp, timestamp) / 1000.0;
+
+        let velocity = mul(sub(point, self.prev), 1.0 / elapsed);
+        let alpha = Self::alpha(Self::DERIVATIVE_CUTOFF, elapsed);
+        self.velocity = lrp(self.velocity, velocity, alpha);
+
+        let speed = dist(self.velocity, [0.0, 0.0]);
+        let cutoff = self.min_cutoff + self.beta * speed;
+        self.prev = lrp(self.prev, point, Self::alpha(cutoff, elapsed));
+        self.prev_timestamp = timestamp.or(self.prev_timestamp);
+        self.prev
+    }
+
+    fn clone_box(&self) -> Box<dyn Smoother> {
+        Box::new(self.clone())
+    }
+}
+
+/// A Kalman filter that follows the pen's position and velocity, so it
+/// keeps up with steady movement while averaging out noise.
+#[derive(Debug, Clone)]
+pub struct KalmanFilter {
+    process_noise: f64,
+    measurement_noise: f64,
+    // The estimated position and velocity (per frame) along each axis
+    state: [[f64; 2]; 2],
+    // How uncertain the estimate is along each axis, as the covariance
+    // matrix `[[position, both], [both, velocity]]`
+    covariance: [[f64; 3]; 2],
+    prev_timestamp: Option<f64>,
+}
+
+impl KalmanFilter {
+    /// A filter for a pen whose speed changes by about the square root of
+    /// `process_noise` units per frame, each frame, and whose position is off
+    /// by about the square root of `measurement_noise` units.
+    pub fn new(process_noise: f64, measurement_noise: f64) -> Self {
+        Self {
+            process_noise: process_noise.max(0.0),
+            measurement_noise: measurement_noise.max(1e-12),
+            state: [[0.0; 2]; 2],
+            covariance: [[0.0; 3]; 2],
+            prev_timestamp: None,
+        }
+    }
+}
+
+impl Smoother for KalmanFilter {
+    fn start(&mut self, point: [f64; 2], timestamp: Option<f64>) {
+        self.state = [[point[0], 0.0], [point[1], 0.0]];
+        // The first point is where the pen is, but its speed is unknown
+        self.covariance = [[0.0, 0.0, self.measurement_noise]; 2];
+        self.prev_timestamp = timestamp;
+    }
+
+    fn smooth(&mut self, point: [f64; 2], timestamp: Option<f64>) -> [f64; 2] {
+        let dt = elapsed(self.prev_timestamp, timestamp) / FRAME_DURATION;
+        let q = self.process_noise;
+        let r = self.measurement_noise;
+
+        for (axis, &measured) in point.iter().enumerate() {
+            let [x, v] = self.state[axis];
+            let [pxx, pxv, pvv] = self.covariance[axis];
+
+            // Predict where the pen is now from its velocity, and grow the
+            // uncertainty for changes in velocity along the way
+            let x = x + v * dt;
+            let pxx = pxx + 2.0 * dt * pxv + dt * dt * pvv + q * dt.powi(3) / 3.0;
+            let pxv = pxv + dt * pvv + q * dt * dt / 2.0;
+            let pvv = pvv + q * dt;
+
+            // Correct the prediction with the measured point
+            let residual = measured - x;
+            let s = pxx + r;
+            let (kx, kv) = (pxx / s, pxv / s);
+            self.state[axis] = [x + kx * residual, v + kv * residual];
+            self.covariance[axis] = [(1.0 - kx) * pxx, (1.0 - kx) * pxv, pvv - kv * pxv];
+        }
+
+        self.prev_timestamp = timestamp.or(self.prev_timestamp);
+        [self.state[0][0], self.state[1][0]]
+    }
+
+    fn clone_box(&self) -> Box<dyn Smoother> {
+        Box::new(self.clone())
+    }
+}
+
+/// A brush pulled along behind the pen on a string, which only moves once
+/// the pen is more than the string's length away from it. Small, shaky
+/// movements don't move it at all, and corners are cut smoothly.
+#[derive(Debug, Clone)]
+pub struct LazyBrush {
+    radius: f64,
+    brush: [f64; 2],
+}
+
+impl LazyBrush {
+    /// A brush on a string `radius` units long.
+    pub fn new(radius: f64) -> Self {
+        Self {
+            radius: radius.max(0.0),
+            brush: [0.0, 0.0],
+        }
+    }
+}
+
+impl Smoother for LazyBrush {
+    fn start(&mut self, point: [f64; 2], _timestamp: Option<f64>) {
+        self.brush = point;
+    }
+
+    fn smooth(&mut self, point: [f64; 2], _timestamp: Option<f64>) -> [f64; 2] {
+        if dist(self.brush, point) > self.radius {
+            self.brush = add(point, mul(uni(sub(self.brush, point)), self.radius));
+        }
+
+        self.brush
+    }
+
+    fn clone_box(&self) -> Box<dyn Smoother> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,393 @@
+use crate::find_end_hook::find_end_hook;
+use crate::get_stroke_outline_points::{
+    assemble_outline, end_cap, start_cap, OutlineCursor, OutlineParams, OutlineSide,
+};
+use crate::get_stroke_points::{
+    get_stroke_points, normalize_point, set_first_vector, StrokePointsState,
+};
+use crate::resample_points::Resampler;
+use crate::types::{InputPoint, StrokeOptions, StrokePoint, TaperOptions, TaperType};
+
+/// The part of a stroke's outline that changed after a call to
+/// `StrokeBuilder::push` or `StrokeBuilder::finish`.
+///
+/// Points before `left_start` and `right_start` are unchanged since the
+/// previous update; everything from those indices on should be replaced
+/// with `left` and `right`.
+#[derive(Debug, Clone)]
+pub struct StrokeDelta {
+    pub left_start: usize,
+    pub left: Vec<[f64; 2]>,
+    pub right_start: usize,
+    pub right: Vec<[f64; 2]>,
+    pub start_cap: Vec<[f64; 2]>,
+    pub end_cap: Vec<[f64; 2]>,
+}
+
+/// Builds a stroke one input point at a time, for live drawing.
+///
+/// Each call to `push` only reworks the end of the stroke that can still
+/// change: the streamline state, running length and simulated pressure are
+/// kept between calls, as are the parts of the outline that later points
+/// can no longer affect. After `finish`, `outline` returns the same points
+/// as `get_stroke` with `last` set to `true`.
+///
+/// A stroke tapered with `TaperType::Bool(true)` tapers over its whole
+/// length, so its outline is rebuilt on every update.
+#[derive(Debug, Clone)]
+pub struct StrokeBuilder {
+    options: StrokeOptions,
+    input_points: Vec<InputPoint>,
+    // The resampling state after every input point, when resampling
+    resampler: Option<Resampler>,
+    // The input points after resampling, of which the first
+    // `stable_samples` won't change, and the first `fed` have been run
+    // through the stroke points state
+    samples: Vec<InputPoint>,
+    stable_samples: usize,
+    fed: usize,
+    // The stroke points state after every sample but the last one
+    state: Option<StrokePointsState>,
+    stroke_points: Vec<StrokePoint>,
+    // The number of stroke points that later input points can't change
+    committed: usize,
+    left_pts: OutlineSide,
+    right_pts: OutlineSide,
+    // The outline state after the stroke points that can't change any more
+    cursor: Option<OutlineCursor>,
+    cursor_index: usize,
+    stable_left: usize,
+    stable_right: usize,
+    start_cap: Vec<[f64; 2]>,
+    end_cap: Vec<[f64; 2]>,
+    // While the stroke is drawn with its end hook trimmed off, the builder
+    // for the whole stroke, which the next update carries on from
+    untrimmed: Option<Box<StrokeBuilder>>,
+    // Whether the outline was last drawn by a different builder, so that
+    // the next delta has to replace all of it
+    is_redrawn: bool,
+}
+
+impl StrokeBuilder {
+    pub fn new(options: StrokeOptions) -> Self {
+        Self {
+            options,
+            input_points: Vec::new(),
+            resampler: None,
+            samples: Vec::new(),
+            stable_samples: 0,
+            fed: 1,
+            state: None,
+            stroke_points: Vec::new(),
+            committed: 0,
+            left_pts: OutlineSide::default(),
+            right_pts: OutlineSide::default(),
+            cursor: None,
+            cursor_index: 1,
+            stable_left: 0,
+            stable_right: 0,
+            start_cap: Vec::new(),
+            end_cap: Vec::new(),
+            untrimmed: None,
+            is_redrawn: false,
+        }
+    }
+
+    /// Add an input point to the stroke.
+    pub fn push(&mut self, point: InputPoint) -> StrokeDelta {
+        self.restore_hook();
+        self.resample(&point);
+        self.input_points.push(point);
+        self.commit_stable_samples();
+        self.update(self.options.last.unwrap_or(false), &[])
+    }
+
+    /// Treat the points as a completed stroke.
+    pub fn finish(&mut self) -> StrokeDelta {
+        self.restore_hook();
+        self.update(true, &[])
+    }
+
+    /// Draw the stroke with predicted points, such as those from
+    /// `predict_points`, added to its end. The predicted points aren't kept:
+    /// the next call to `push`, `finish` or `predict` draws the stroke
+    /// without them, as if they had never been added.
+    pub fn predict(&mut self, predicted: &[InputPoint]) -> StrokeDelta {
+        self.restore_hook();
+
+        // There's nothing to carry on from before the first point
+        let predicted = if self.samples.is_empty() { &[] } else { predicted };
+        self.update(self.options.last.unwrap_or(false), predicted)
+    }
+
+    /// The input points added so far.
+    pub fn input_points(&self) -> &[InputPoint] {
+        &self.input_points
+    }
+
+    /// The stroke points, as returned by `get_stroke_points`.
+    pub fn stroke_points(&self) -> &[StrokePoint] {
+        &self.stroke_points
+    }
+
+    /// The left side of the outline, from start to end.
+    pub fn left(&self) -> &[[f64; 2]] {
+        &self.left_pts.points
+    }
+
+    /// The right side of the outline, from start to end.
+    pub fn right(&self) -> &[[f64; 2]] {
+        &self.right_pts.points
+    }
+
+    /// The full outline polygon, as returned by `get_stroke`.
+    pub fn outline(&self) -> Vec<[f64; 2]> {
+        if self.stroke_points.is_empty() || self.options.size.unwrap_or(16.0) <= 0.0 {
+            return vec![];
+        }
+
+        let params = OutlineParams::new(&self.stroke_points, &self.options);
+        assemble_outline(
+            &params,
+            &self.start_cap,
+            &self.right_pts.points,
+            &self.end_cap,
+            &self.left_pts.points,
+        )
+    }
+
+    /// Add an input point to the samples, resampling it if asked to. The
+    /// samples after the stable ones are placed again for each new point.
+    fn resample(&mut self, point: &InputPoint) {
+        self.samples.truncate(self.stable_samples);
+
+        match (self.options.resampling, self.resampler.as_mut()) {
+            (Some(resampling), None) => {
+                self.resampler = Some(Resampler::new(resampling, point));
+                self.samples.push(point.clone());
+            }
+            (Some(_), Some(resampler)) => resampler.push(point, &mut self.samples),
+            (None, _) => self.samples.push(point.clone()),
+        }
+
+        self.stable_samples = self.samples.len();
+        if let Some(resampler) = self.resampler.as_ref() {
+            resampler.clone().finish(&mut self.samples);
+        }
+    }
+
+    /// Run the stable samples before the newest one through the stroke
+    /// points state. Only the newest sample is treated as the end of the
+    /// stroke, so the stroke points created here won't change again.
+    fn commit_stable_samples(&mut self) {
+        let count = self.samples.len();
+
+        if self.state.is_none() {
+            let first = normalize_point(&self.samples[0]);
+            let state = StrokePointsState::new(first, &self.options);
+            self.stroke_points = vec![state.prev().clone()];
+            self.committed = 1;
+            self.state = Some(state);
+            return;
+        }
+
+        // Two input points are filled in as a special case by `get_stroke_points`
+        if count < 3 {
+            return;
+        }
+
+        let Some(state) = self.state.as_mut() else {
+            return;
+        };
+
+        self.stroke_points.truncate(self.committed);
+
+        let end = self.stable_samples.min(count - 1);
+        for sample in &self.samples[self.fed.min(end)..end] {
+            state.push(normalize_point(sample), false, false, &mut self.stroke_points);
+        }
+        self.fed = self.fed.max(end);
+
+        self.committed = self.stroke_points.len();
+    }
+
+    fn update(&mut self, is_complete: bool, predicted: &[InputPoint]) -> StrokeDelta {
+        let hook = match self.options.hook_removal.as_ref() {
+            Some(hook_removal) if is_complete && predicted.is_empty() => {
+                find_end_hook(&self.input_points, hook_removal)
+            }
+            _ => None,
+        };
+        if let Some(hook) = hook {
+            self.trim_hook(hook);
+            return self.delta(0, 0);
+        }
+
+        // The samples after the stable ones, with any predicted points
+        let predicted_tail = (!predicted.is_empty()).then(|| self.predicted_tail(predicted));
+        let (stable, tail) = self.samples.split_at(self.stable_samples);
+        let tail = predicted_tail.as_deref().unwrap_or(tail);
+        let count = stable.len() + tail.len();
+
+        if count < 3 {
+            // The samples are already resampled
+            let options = StrokeOptions {
+                last: Some(is_complete),
+                resampling: None,
+                ..self.options.clone()
+            };
+            let samples: Vec<InputPoint> = stable.iter().chain(tail).cloned().collect();
+            self.stroke_points = get_stroke_points(&samples, &options);
+        } else if let Some(state) = self.state.as_ref() {
+            // Run the samples that can still change through a copy of the
+            // state, as the next input point will replace them
+            self.stroke_points.truncate(self.committed);
+            let mut state = state.clone();
+            let unfed = count - self.fed;
+            for (i, sample) in stable[self.fed..].iter().chain(tail).enumerate() {
+                let is_max = i == unfed - 1;
+                let input = normalize_point(sample);
+                state.push(input, is_max, is_complete, &mut self.stroke_points);
+            }
+            set_first_vector(&mut self.stroke_points);
+        }
+
+        let (left_start, right_start) = if std::mem::take(&mut self.is_redrawn) {
+            (0, 0)
+        } else {
+            (self.stable_left, self.stable_right)
+        };
+
+        self.update_outline(count);
+
+        self.delta(left_start, right_start)
+    }
+
+    fn delta(&self, left_start: usize, right_start: usize) -> StrokeDelta {
+        StrokeDelta {
+            left_start,
+            left: self.left_pts.points[left_start..].to_vec(),
+            right_start,
+            right: self.right_pts.points[right_start..].to_vec(),
+            start_cap: self.start_cap.clone(),
+            end_cap: self.end_cap.clone(),
+        }
+    }
+
+    /// Draw the completed stroke without the input points from `hook` on.
+    /// Points that are already part of the stable outline may be trimmed,
+    /// so a new builder draws the stroke up to the hook, and this one is
+    /// kept aside until the next update.
+    fn trim_hook(&mut self, hook: usize) {
+        let mut trimmed = StrokeBuilder::new(StrokeOptions {
+            hook_removal: None,
+            ..self.options.clone()
+        });
+        for point in &self.input_points[..hook] {
+            trimmed.push(point.clone());
+        }
+        trimmed.update(true, &[]);
+
+        trimmed.options = self.options.clone();
+        trimmed.input_points = self.input_points.clone();
+        let untrimmed = std::mem::replace(self, trimmed);
+        self.untrimmed = Some(Box::new(untrimmed));
+    }
+
+    /// Go back to the builder for the whole stroke after drawing it with its
+    /// end hook trimmed off.
+    fn restore_hook(&mut self) {
+        if let Some(untrimmed) = self.untrimmed.take() {
+            *self = *untrimmed;
+            self.is_redrawn = true;
+        }
+    }
+
+    /// The samples after the stable ones if the predicted points were added.
+    fn predicted_tail(&self, predicted: &[InputPoint]) -> Vec<InputPoint> {
+        let mut tail = Vec::new();
+
+        match self.resampler.as_ref() {
+            Some(resampler) => {
+                let mut resampler = resampler.clone();
+                for point in predicted {
+                    resampler.push(point, &mut tail);
+                }
+                resampler.finish(&mut tail);
+            }
+            None => {
+                tail.extend_from_slice(&self.samples[self.stable_samples..]);
+                tail.extend_from_slice(predicted);
+            }
+        }
+
+        tail
+    }
+
+    fn update_outline(&mut self, count: usize) {
+        self.left_pts.truncate(self.stable_left);
+        self.right_pts.truncate(self.stable_right);
+        self.start_cap.clear();
+        self.end_cap.clear();
+
+        let points = &self.stroke_points;
+        let params = OutlineParams::new(points, &self.options);
+
+        if points.is_empty() || params.size <= 0.0 {
+            return;
+        }
+
+        // Move the stable part of the outline forward. The starting pressure
+        // is averaged over the first ten points, and a taper of `true` depends
+        // on the length of the whole stroke, so until then nothing is stable.
+        let is_taper_fixed = |taper: &Option<TaperOptions>| {
+            !matches!(
+                taper.as_ref().and_then(|t| t.taper.as_ref()),
+                Some(TaperType::Bool(true))
+            )
+        };
+
+        if count >= 3
+            && self.committed >= 10
+            && is_taper_fixed(&self.options.start)
+            && is_taper_fixed(&self.options.end)
+        {
+            let committed_length = points[self.committed - 1].running_length;
+            let cursor = self
+                .cursor
+                .get_or_insert_with(|| OutlineCursor::new(points, &params));
+
+            while self.cursor_index < self.committed
+                && committed_length - points[self.cursor_index].running_length
+                    >= params.taper_end
+            {
+                cursor.push(
+                    &points[self.cursor_index],
+                    &params,
+                    &mut self.left_pts,
+                    &mut self.right_pts,
+                );
+                self.cursor_index += 1;
+            }
+
+            self.stable_left = self.left_pts.len();
+            self.stable_right = self.right_pts.len();
+        }
+
+        // Then build the rest of the outline from a copy of the stable state
+        let mut cursor = match self.cursor {
+            Some(cursor) => cursor,
+            None => OutlineCursor::new(points, &params),
+        };
+
+        for curr in &points[self.cursor_index..] {
+            cursor.push(curr, &params, &mut self.left_pts, &mut self.right_pts);
+        }
+        cursor.finish(&mut self.left_pts, &mut self.right_pts);
+
+        self.start_cap = start_cap(points, &params);
+        if !self.right_pts.is_empty() {
+            self.end_cap = end_cap(points, &params);
+        }
+    }
+}
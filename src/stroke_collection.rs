@@ -0,0 +1,278 @@
+use crate::hit_test::distance_to_outline;
+use crate::polygon::winding_number;
+use crate::types::{Bounds, Containment};
+use std::collections::{BTreeMap, HashMap};
+
+// The default width and height of the grid's cells
+const DEFAULT_CELL_SIZE: f64 = 256.0;
+
+// Strokes that would cover more cells than this are kept out of the grid
+// and checked on every query
+const MAX_CELLS_PER_STROKE: i64 = 64;
+
+/// Identifies a stroke in a `StrokeCollection`. Ids aren't reused, and
+/// later strokes have larger ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StrokeId(pub u64);
+
+#[derive(Debug, Clone)]
+struct Entry {
+    outline: Vec<[f64; 2]>,
+    bounds: Option<Bounds>,
+}
+
+/// The outlines of many strokes, indexed by where they are so that finding
+/// the strokes in a viewport or under the cursor doesn't check every stroke.
+///
+/// The outlines are the ones from `get_stroke`. They're kept in a grid of
+/// square cells by their bounds, and query results are in the order the
+/// strokes were added.
+#[derive(Debug, Clone)]
+pub struct StrokeCollection {
+    cell_size: f64,
+    strokes: BTreeMap<StrokeId, Entry>,
+    cells: HashMap<[i64; 2], Vec<StrokeId>>,
+    // Strokes too big for the grid
+    large: Vec<StrokeId>,
+    next_id: u64,
+}
+
+impl Default for StrokeCollection {
+    fn default() -> Self {
+        Self::with_cell_size(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl StrokeCollection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An empty collection with a grid of cells of the given size. Cells a
+    /// little larger than a typical stroke work best.
+    pub fn with_cell_size(cell_size: f64) -> Self {
+        Self {
+            cell_size: if cell_size > 0.0 { cell_size } else { DEFAULT_CELL_SIZE },
+            strokes: BTreeMap::new(),
+            cells: HashMap::new(),
+            large: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.strokes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strokes.is_empty()
+    }
+
+    /// Add a stroke's outline, returning the id to find it by.
+    pub fn insert(&mut self, outline: Vec<[f64; 2]>) -> StrokeId {
+        let id = StrokeId(self.next_id);
+        self.next_id += 1;
+
+        let bounds = Bounds::from_points(&outline);
+        if let Some(bounds) = bounds {
+            match self.cell_range(&bounds) {
+                Some((min, max)) => {
+                    for x in min[0]..=max[0] {
+                        for y in min[1]..=max[1] {
+                            self.cells.entry([x, y]).or_default().push(id);
+                        }
+                    }
+                }
+                None => self.large.push(id),
+            }
+        }
+
+        self.strokes.insert(id, Entry { outline, bounds });
+        id
+    }
+
+    /// Remove a stroke, returning its outline if it was in the collection.
+    pub fn remove(&mut self, id: StrokeId) -> Option<Vec<[f64; 2]>> {
+        let entry = self.strokes.remove(&id)?;
+
+        if let Some(bounds) = entry.bounds {
+            match self.cell_range(&bounds) {
+                Some((min, max)) => {
+                    for x in min[0]..=max[0] {
+                        for y in min[1]..=max[1] {
+                            if let Some(cell) = self.cells.get_mut(&[x, y]) {
+                                cell.retain(|&other| other != id);
+                                if cell.is_empty() {
+                                    self.cells.remove(&[x, y]);
+                                }
+                            }
+                        }
+                    }
+                }
+                None => self.large.retain(|&other| other != id),
+            }
+        }
+
+        Some(entry.outline)
+    }
+
+    /// A stroke's outline.
+    pub fn get(&self, id: StrokeId) -> Option<&[[f64; 2]]> {
+        self.strokes.get(&id).map(|entry| entry.outline.as_slice())
+    }
+
+    /// The bounds of a stroke's outline, or `None` if it's empty or not in
+    /// the collection.
+    pub fn bounds(&self, id: StrokeId) -> Option<Bounds> {
+        self.strokes.get(&id).and_then(|entry| entry.bounds)
+    }
+
+    /// Every stroke and its outline, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = (StrokeId, &[[f64; 2]])> {
+        self.strokes.iter().map(|(&id, entry)| (id, entry.outline.as_slice()))
+    }
+
+    /// The strokes whose bounds overlap a viewport, to draw it. Some of them
+    /// may only come close to the viewport without reaching into it.
+    pub fn query_viewport(&self, viewport: &Bounds) -> Vec<StrokeId> {
+        self.candidates(viewport)
+    }
+
+    /// The strokes that a selection box touches, and whether each is wholly
+    /// `Inside` the box or only `Partial`ly.
+    pub fn select_box(&self, selection: &Bounds) -> Vec<(StrokeId, Containment)> {
+        self.candidates(selection)
+            .into_iter()
+            .filter_map(|id| {
+                let entry = &self.strokes[&id];
+                if selection.contains(&entry.bounds?) {
+                    Some((id, Containment::Inside))
+                } else if touches_box(&entry.outline, selection) {
+                    Some((id, Containment::Partial))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// The stroke nearest to a point, with its distance from the point to
+    /// its edge, which is negative when the point is inside it. Strokes more
+    /// than `max_distance` away are left out.
+    pub fn nearest(&self, point: [f64; 2], max_distance: f64) -> Option<(StrokeId, f64)> {
+        let area = Bounds {
+            min: point,
+            max: point,
+        }
+        .expand(max_distance.max(0.0));
+
+        // Check the strokes with the nearest bounds first, which are never
+        // further from the point than the strokes themselves
+        let mut candidates: Vec<(f64, StrokeId)> = self
+            .candidates(&area)
+            .into_iter()
+            .filter_map(|id| Some((distance_to_bounds(point, &self.strokes[&id].bounds?), id)))
+            .collect();
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut nearest: Option<(StrokeId, f64)> = None;
+        for (bounds_distance, id) in candidates {
+            // Bounds that hold the point are 0 away, but the point may still
+            // be deeper inside their stroke than inside the nearest so far
+            if nearest.is_some_and(|(_, distance)| bounds_distance > distance.max(0.0)) {
+                break;
+            }
+
+            let Some(distance) = distance_to_outline(point, &self.strokes[&id].outline) else {
+                continue;
+            };
+            let is_nearer = nearest.map_or(true, |(_, nearest)| distance < nearest);
+            if distance <= max_distance && is_nearer {
+                nearest = Some((id, distance));
+            }
+        }
+
+        nearest
+    }
+
+    /// The cells that some bounds cover, from the lowest to the highest, or
+    /// `None` if there are too many of them for the grid.
+    fn cell_range(&self, bounds: &Bounds) -> Option<([i64; 2], [i64; 2])> {
+        let cell = |n: f64| (n / self.cell_size).floor();
+        let min = bounds.min.map(cell);
+        let max = bounds.max.map(cell);
+
+        let cells = (max[0] - min[0] + 1.0) * (max[1] - min[1] + 1.0);
+        if !cells.is_finite() || cells > MAX_CELLS_PER_STROKE as f64 {
+            return None;
+        }
+
+        Some((min.map(|n| n as i64), max.map(|n| n as i64)))
+    }
+
+    /// The strokes whose bounds overlap an area, in the order they were added.
+    fn candidates(&self, area: &Bounds) -> Vec<StrokeId> {
+        let overlaps = |id: &StrokeId| {
+            self.strokes[id]
+                .bounds
+                .is_some_and(|bounds| bounds.intersects(area))
+        };
+
+        // Look through the cells the area covers, unless there are more of
+        // them than there are strokes
+        let cell = |n: f64| (n / self.cell_size).floor();
+        let (min, max) = (area.min.map(cell), area.max.map(cell));
+        let cells = (max[0] - min[0] + 1.0) * (max[1] - min[1] + 1.0);
+        if !cells.is_finite() || cells > self.cells.len() as f64 {
+            return self.strokes.keys().copied().filter(overlaps).collect();
+        }
+
+        let mut ids: Vec<StrokeId> = self.large.clone();
+        for x in min[0] as i64..=max[0] as i64 {
+            for y in min[1] as i64..=max[1] as i64 {
+                if let Some(cell) = self.cells.get(&[x, y]) {
+                    ids.extend_from_slice(cell);
+                }
+            }
+        }
+
+        ids.sort();
+        ids.dedup();
+        ids.retain(overlaps);
+        ids
+    }
+}
+
+/// How far a point is from some bounds, or 0 inside them.
+fn distance_to_bounds(point: [f64; 2], bounds: &Bounds) -> f64 {
+    let dx = f64::max(0.0, f64::max(bounds.min[0] - point[0], point[0] - bounds.max[0]));
+    let dy = f64::max(0.0, f64::max(bounds.min[1] - point[1], point[1] - bounds.max[1]));
+    dx.hypot(dy)
+}
+
+/// Whether an outline reaches into a box, or the box is inside the outline.
+fn touches_box(outline: &[[f64; 2]], selection: &Bounds) -> bool {
+    // An edge of the outline runs through the box
+    let count = outline.len();
+    let crosses = (0..count).any(|i| {
+        let (a, b) = (outline[i], outline[(i + 1) % count]);
+        let mut range = (0.0, 1.0);
+        for axis in 0..2 {
+            let (x, dx) = (a[axis], b[axis] - a[axis]);
+            let (low, high) = (selection.min[axis], selection.max[axis]);
+
+            if dx == 0.0 {
+                if x < low || x > high {
+                    return false;
+                }
+                continue;
+            }
+
+            let (t0, t1) = ((low - x) / dx, (high - x) / dx);
+            range = (f64::max(range.0, t0.min(t1)), f64::min(range.1, t0.max(t1)));
+        }
+        range.0 <= range.1
+    });
+
+    crosses || count > 0 && winding_number(selection.min, outline) != 0
+}
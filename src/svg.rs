@@ -0,0 +1,594 @@
+use crate::path::{draw_stroke, PathSink};
+use crate::types::{Bounds, FillRule, SvgCurve, SvgPathOptions};
+use crate::vec::{add, dist, len, lrp, mul, sub};
+
+/// A path sink that writes SVG path data.
+///
+/// By default, every coordinate is written with two decimals and quadratic
+/// curves that continue smoothly from the previous one are written as `T`
+/// commands. `SvgPathOptions` trades that for smaller output.
+#[derive(Debug, Clone, Default)]
+pub struct SvgPath {
+    options: SvgPathOptions,
+    data: String,
+    // The last command letter written, or `None` after a number
+    last_command: Option<char>,
+    // The start of the current subpath and the current point, as a reader
+    // of the path data sees them
+    start: [f64; 2],
+    current: [f64; 2],
+    // The current point as it was given
+    exact_current: [f64; 2],
+    // The control point of the previous quadratic curve, as it was given
+    // and as a reader sees it
+    quad_control: Option<([f64; 2], [f64; 2])>,
+}
+
+impl SvgPath {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_options(options: SvgPathOptions) -> Self {
+        Self {
+            options,
+            ..Self::default()
+        }
+    }
+
+    /// The path data written so far.
+    pub fn as_str(&self) -> &str {
+        &self.data
+    }
+
+    pub fn into_string(self) -> String {
+        self.data
+    }
+
+    fn precision(&self) -> usize {
+        self.options.precision.unwrap_or(2)
+    }
+
+    fn is_relative(&self) -> bool {
+        self.options.relative.unwrap_or(false)
+    }
+
+    fn is_minified(&self) -> bool {
+        self.options.minify.unwrap_or(false)
+    }
+
+    /// Whether the ends of smooth curves make up for the rounding of their
+    /// control points, which is only done once the output is customized.
+    fn compensates_rounding(&self) -> bool {
+        self.options.precision.is_some() || self.is_minified() || self.is_relative()
+    }
+
+    /// Round a point to the precision it will be written with.
+    fn round(&self, point: [f64; 2]) -> [f64; 2] {
+        let precision = self.precision();
+        point.map(|n| format!("{:.*}", precision, n).parse().unwrap_or(n))
+    }
+
+    fn format_number(&self, n: f64) -> String {
+        let number = format!("{:.*}", self.precision(), n);
+        if !self.is_minified() {
+            return number;
+        }
+
+        // Drop the trailing zeros, a leading zero and the sign of zero
+        let number = if number.contains('.') {
+            number.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            &number
+        };
+        let number = match number {
+            "-0" => "0",
+            _ => number,
+        };
+
+        if let Some(rest) = number.strip_prefix("0.") {
+            format!(".{}", rest)
+        } else if let Some(rest) = number.strip_prefix("-0.") {
+            format!("-.{}", rest)
+        } else {
+            number.to_string()
+        }
+    }
+
+    /// Write a command with its points, which are moved relative to the
+    /// current point if needed. Returns the last point, rounded.
+    fn command(&mut self, letter: char, points: &[[f64; 2]]) -> [f64; 2] {
+        let is_relative = self.is_relative();
+        let letter = if is_relative {
+            letter.to_ascii_lowercase()
+        } else {
+            letter
+        };
+
+        let origin = self.current;
+        let mut numbers = Vec::with_capacity(points.len() * 2);
+        let mut last = origin;
+        for &point in points {
+            last = self.round(point);
+            let written = if is_relative { sub(last, origin) } else { last };
+            numbers.push(self.format_number(written[0]));
+            numbers.push(self.format_number(written[1]));
+        }
+
+        if self.is_minified() {
+            self.write_minified(letter, &numbers);
+        } else {
+            self.write_spaced(letter, &numbers);
+        }
+
+        last
+    }
+
+    /// Write a command with spaces between the commands and their points,
+    /// and commas within the points. Runs of `T` commands share one letter.
+    fn write_spaced(&mut self, letter: char, numbers: &[String]) {
+        let is_smooth = letter.eq_ignore_ascii_case(&'T');
+
+        if letter.eq_ignore_ascii_case(&'Z') {
+            self.data.push(letter);
+        } else {
+            if self.last_command != Some(letter) || !is_smooth {
+                if !self.data.is_empty() {
+                    self.data.push(' ');
+                }
+                self.data.push(letter);
+            }
+
+            let pairs: Vec<String> = numbers.chunks(2).map(|n| n.join(",")).collect();
+            self.data.push_str(&pairs.join(" "));
+
+            if is_smooth {
+                self.data.push(' ');
+            }
+        }
+
+        self.last_command = Some(letter);
+    }
+
+    /// Write a command with as few characters as possible. The letter is left
+    /// out when it repeats, and numbers are only spaced where they'd run together.
+    fn write_minified(&mut self, letter: char, numbers: &[String]) {
+        let is_move = letter.eq_ignore_ascii_case(&'M');
+        let repeats = self.last_command == Some(letter) && !is_move;
+
+        if !repeats {
+            self.data.push(letter);
+        }
+
+        for (i, number) in numbers.iter().enumerate() {
+            let follows_number = i > 0 || repeats;
+            let previous_has_point = self
+                .data
+                .rsplit(|c: char| c == '-' || c == ' ' || c.is_ascii_alphabetic())
+                .next()
+                .is_some_and(|previous| previous.contains('.'));
+
+            let needs_space = follows_number
+                && !number.starts_with('-')
+                && !(number.starts_with('.') && previous_has_point);
+
+            if needs_space {
+                self.data.push(' ');
+            }
+            self.data.push_str(number);
+        }
+
+        self.last_command = Some(letter);
+    }
+
+    /// How many straight lines to split a curve into so that they stay
+    /// within the tolerance of it, from the curve's largest second difference.
+    fn flatten_steps(&self, second_difference: f64) -> usize {
+        let tolerance = match self.options.curve.unwrap_or_default() {
+            SvgCurve::Polyline { tolerance } if tolerance > 0.0 => tolerance,
+            _ => return 1,
+        };
+
+        (second_difference / (8.0 * tolerance)).sqrt().ceil().clamp(1.0, 1024.0) as usize
+    }
+}
+
+impl PathSink for SvgPath {
+    fn move_to(&mut self, point: [f64; 2]) {
+        self.exact_current = point;
+        self.start = self.command('M', &[point]);
+        self.current = self.start;
+        self.quad_control = None;
+    }
+
+    fn line_to(&mut self, point: [f64; 2]) {
+        self.exact_current = point;
+        self.current = self.command('L', &[point]);
+        self.quad_control = None;
+    }
+
+    fn quad_to(&mut self, control: [f64; 2], point: [f64; 2]) {
+        match self.options.curve.unwrap_or_default() {
+            SvgCurve::Smooth => {
+                // A smooth curve's control point is the previous one reflected
+                // through the current point
+                let is_smooth = self.quad_control.is_some_and(|(prev, _)| {
+                    let reflected = sub(mul(self.exact_current, 2.0), prev);
+                    dist(reflected, control) <= 1e-9 * (1.0 + len(control))
+                });
+
+                // A reader reflects the control point it has, so rounding errors
+                // would build up along a run of smooth curves. Rounding the end
+                // of each curve to make up for the error in its control point
+                // keeps both within one unit of the last decimal. Without any
+                // options, the ends are written as they are, as they always were.
+                let (letter, written_control) = match self.quad_control {
+                    Some((_, prev)) if is_smooth => ('T', sub(mul(self.current, 2.0), prev)),
+                    _ => ('Q', self.round(control)),
+                };
+                let end = if self.compensates_rounding() {
+                    add(point, mul(sub(written_control, control), 0.5))
+                } else {
+                    point
+                };
+
+                self.current = if letter == 'T' {
+                    self.command('T', &[end])
+                } else {
+                    self.command('Q', &[control, end])
+                };
+                self.exact_current = point;
+                self.quad_control = Some((control, written_control));
+            }
+            SvgCurve::Quadratic => {
+                self.exact_current = point;
+                self.current = self.command('Q', &[control, point]);
+            }
+            SvgCurve::Cubic => {
+                // The same curve, raised to a cubic
+                let from = self.current;
+                let control1 = lrp(from, control, 2.0 / 3.0);
+                let control2 = lrp(point, control, 2.0 / 3.0);
+                self.cubic_to(control1, control2, point);
+            }
+            SvgCurve::Polyline { .. } => {
+                let from = self.current;
+                let steps = self.flatten_steps(2.0 * len(add(sub(from, mul(control, 2.0)), point)));
+                for i in 1..=steps {
+                    let t = i as f64 / steps as f64;
+                    self.line_to(lrp(lrp(from, control, t), lrp(control, point, t), t));
+                }
+            }
+        }
+    }
+
+    fn cubic_to(&mut self, control1: [f64; 2], control2: [f64; 2], point: [f64; 2]) {
+        if let SvgCurve::Polyline { .. } = self.options.curve.unwrap_or_default() {
+            let from = self.current;
+            let second_difference = f64::max(
+                len(add(sub(from, mul(control1, 2.0)), control2)),
+                len(add(sub(control1, mul(control2, 2.0)), point)),
+            );
+            let steps = self.flatten_steps(6.0 * second_difference);
+            for i in 1..=steps {
+                let t = i as f64 / steps as f64;
+                let a = lrp(lrp(from, control1, t), lrp(control1, control2, t), t);
+                let b = lrp(lrp(control1, control2, t), lrp(control2, point, t), t);
+                self.line_to(lrp(a, b, t));
+            }
+            return;
+        }
+
+        self.exact_current = point;
+        self.current = self.command('C', &[control1, control2, point]);
+        self.quad_control = None;
+    }
+
+    fn close(&mut self) {
+        self.command('Z', &[]);
+        self.current = self.start;
+        self.exact_current = self.start;
+        self.quad_control = None;
+    }
+}
+
+/// A filled outline in an `SvgDocument`, drawn as one path.
+///
+/// # Fields
+/// - `rings`: The closed outlines that make up the path, such as the outline
+///   from `get_stroke`, the rings of a `ClosedStroke` or of each `Polygon`
+/// - `fill`: The fill colour, or black if not set
+/// - `opacity`: From 0 (transparent) to 1 (opaque)
+/// - `fill_rule`: Overrides the document's fill rule for this path
+/// - `stroke`, `stroke_width`: A line drawn along the outline, which is
+///   counted in the document's bounds
+#[derive(Debug, Clone, Default)]
+pub struct SvgStroke {
+    pub rings: Vec<Vec<[f64; 2]>>,
+    pub fill: Option<String>,
+    pub opacity: Option<f64>,
+    pub fill_rule: Option<FillRule>,
+    pub stroke: Option<String>,
+    pub stroke_width: Option<f64>,
+}
+
+impl SvgStroke {
+    /// A stroke from a single outline.
+    pub fn new(outline: Vec<[f64; 2]>) -> Self {
+        Self::from_rings(vec![outline])
+    }
+
+    pub fn from_rings(rings: Vec<Vec<[f64; 2]>>) -> Self {
+        Self {
+            rings,
+            ..Self::default()
+        }
+    }
+
+    // The rings that draw_stroke draws anything for
+    fn drawn_rings(&self) -> impl Iterator<Item = &Vec<[f64; 2]>> {
+        self.rings.iter().filter(|ring| ring.len() >= 4)
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        // The curves stay within the points they're drawn through
+        let bounds = Bounds::from_points(self.drawn_rings().flatten())?;
+
+        match (&self.stroke, self.stroke_width) {
+            (Some(stroke), width) if stroke != "none" => {
+                Some(bounds.expand(width.unwrap_or(1.0).max(0.0) / 2.0))
+            }
+            _ => Some(bounds),
+        }
+    }
+}
+
+/// A group of strokes and other groups in an `SvgDocument`.
+#[derive(Debug, Clone, Default)]
+pub struct SvgGroup {
+    pub id: Option<String>,
+    /// From 0 (transparent) to 1 (opaque), applied to the group as a whole.
+    pub opacity: Option<f64>,
+    /// Hidden groups are kept in the file but not drawn, and don't count
+    /// towards the document's bounds. Defaults to `true`.
+    pub visible: Option<bool>,
+    pub elements: Vec<SvgElement>,
+}
+
+impl SvgGroup {
+    pub fn add_stroke(&mut self, stroke: SvgStroke) {
+        self.elements.push(SvgElement::Stroke(stroke));
+    }
+
+    /// Add a group inside this one and return it.
+    pub fn add_group(&mut self, id: impl Into<String>) -> &mut SvgGroup {
+        push_group(&mut self.elements, id.into())
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        if !self.visible.unwrap_or(true) {
+            return None;
+        }
+
+        elements_bounds(&self.elements)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum SvgElement {
+    Stroke(SvgStroke),
+    Group(SvgGroup),
+}
+
+/// A standalone SVG file made of many strokes.
+///
+/// The view box fits the outlines that are drawn, plus `padding` on every
+/// side, so thick strokes aren't clipped. Layers are the groups at the top
+/// of the document, which editors show as layers.
+///
+/// # Fields
+/// - `elements`: The strokes and groups, from the bottom to the top
+/// - `padding`: Space around the outlines, in the units of the points
+/// - `fill_rule`: How overlapping outlines are filled, unless a stroke sets
+///   its own. Outlines from `get_stroke` that cross themselves need `NonZero`,
+///   the SVG default
+/// - `path_options`: How the path data is written
+/// - `preserve_aspect_ratio`: How the view box is fitted into a viewport of
+///   another shape, such as `xMidYMid meet`
+#[derive(Debug, Clone, Default)]
+pub struct SvgDocument {
+    pub elements: Vec<SvgElement>,
+    pub padding: Option<f64>,
+    pub fill_rule: Option<FillRule>,
+    pub path_options: Option<SvgPathOptions>,
+    pub preserve_aspect_ratio: Option<String>,
+}
+
+impl SvgDocument {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_stroke(&mut self, stroke: SvgStroke) {
+        self.elements.push(SvgElement::Stroke(stroke));
+    }
+
+    /// Add a layer on top of the others and return it.
+    pub fn add_layer(&mut self, name: impl Into<String>) -> &mut SvgGroup {
+        push_group(&mut self.elements, name.into())
+    }
+
+    /// The bounds of the outlines that are drawn, without the padding, or
+    /// `None` if nothing is drawn.
+    pub fn bounds(&self) -> Option<Bounds> {
+        elements_bounds(&self.elements)
+    }
+
+    /// Write the document as an SVG file.
+    pub fn to_svg(&self) -> String {
+        let path_options = self.path_options.clone().unwrap_or_default();
+        let precision = path_options.precision.unwrap_or(2);
+
+        // Round the view box outwards, so it still fits the outlines once
+        // they're rounded
+        let scale = 10f64.powi(precision.min(15) as i32);
+        let (min, size) = match self.bounds() {
+            Some(bounds) => {
+                let bounds = bounds.expand(self.padding.unwrap_or(0.0).max(0.0));
+                let min = bounds.min.map(|n| (n * scale).floor() / scale);
+                let max = bounds.max.map(|n| (n * scale).ceil() / scale);
+                (min, [max[0] - min[0], max[1] - min[1]])
+            }
+            None => ([0.0; 2], [0.0; 2]),
+        };
+        let number = |n: f64| format_number(n, precision);
+
+        let mut svg = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        svg.push_str("<svg xmlns=\"http://www.w3.org/2000/svg\"");
+        let view_box = [min[0], min[1], size[0], size[1]].map(number).join(" ");
+        svg.push_str(&attribute("viewBox", &view_box));
+        svg.push_str(&attribute("width", &number(size[0])));
+        svg.push_str(&attribute("height", &number(size[1])));
+        if let Some(preserve_aspect_ratio) = &self.preserve_aspect_ratio {
+            svg.push_str(&attribute("preserveAspectRatio", preserve_aspect_ratio));
+        }
+        if let Some(fill_rule) = self.fill_rule {
+            svg.push_str(&attribute("fill-rule", fill_rule_name(fill_rule)));
+        }
+        svg.push_str(">\n");
+
+        write_elements(&mut svg, &self.elements, &path_options, 1);
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+fn push_group(elements: &mut Vec<SvgElement>, id: String) -> &mut SvgGroup {
+    elements.push(SvgElement::Group(SvgGroup {
+        id: Some(id),
+        ..SvgGroup::default()
+    }));
+
+    match elements.last_mut() {
+        Some(SvgElement::Group(group)) => group,
+        _ => unreachable!(),
+    }
+}
+
+fn elements_bounds(elements: &[SvgElement]) -> Option<Bounds> {
+    elements
+        .iter()
+        .filter_map(|element| match element {
+            SvgElement::Stroke(stroke) => stroke.bounds(),
+            SvgElement::Group(group) => group.bounds(),
+        })
+        .reduce(Bounds::union)
+}
+
+fn write_elements(
+    svg: &mut String,
+    elements: &[SvgElement],
+    path_options: &SvgPathOptions,
+    depth: usize,
+) {
+    let indent = "  ".repeat(depth);
+    let precision = path_options.precision.unwrap_or(2);
+
+    for element in elements {
+        match element {
+            SvgElement::Stroke(stroke) => {
+                let mut path = SvgPath::with_options(path_options.clone());
+                for ring in stroke.drawn_rings() {
+                    draw_stroke(ring, true, &mut path);
+                }
+                if path.as_str().is_empty() {
+                    continue;
+                }
+
+                svg.push_str(&indent);
+                svg.push_str("<path");
+                svg.push_str(&attribute("d", path.as_str()));
+                if let Some(fill) = &stroke.fill {
+                    svg.push_str(&attribute("fill", fill));
+                }
+                if let Some(fill_rule) = stroke.fill_rule {
+                    svg.push_str(&attribute("fill-rule", fill_rule_name(fill_rule)));
+                }
+                if let Some(opacity) = stroke.opacity {
+                    svg.push_str(&attribute("opacity", &format_number(opacity.clamp(0.0, 1.0), 3)));
+                }
+                if let Some(color) = &stroke.stroke {
+                    svg.push_str(&attribute("stroke", color));
+                    if let Some(width) = stroke.stroke_width {
+                        svg.push_str(&attribute("stroke-width", &format_number(width, precision)));
+                    }
+                    svg.push_str(&attribute("stroke-linejoin", "round"));
+                    svg.push_str(&attribute("stroke-linecap", "round"));
+                }
+                svg.push_str("/>\n");
+            }
+            SvgElement::Group(group) => {
+                svg.push_str(&indent);
+                svg.push_str("<g");
+                if let Some(id) = &group.id {
+                    svg.push_str(&attribute("id", id));
+                }
+                if let Some(opacity) = group.opacity {
+                    svg.push_str(&attribute("opacity", &format_number(opacity.clamp(0.0, 1.0), 3)));
+                }
+                if !group.visible.unwrap_or(true) {
+                    svg.push_str(&attribute("display", "none"));
+                }
+
+                if group.elements.is_empty() {
+                    svg.push_str("/>\n");
+                    continue;
+                }
+
+                svg.push_str(">\n");
+                write_elements(svg, &group.elements, path_options, depth + 1);
+                svg.push_str(&indent);
+                svg.push_str("</g>\n");
+            }
+        }
+    }
+}
+
+/// An attribute with its value escaped, and a space before it.
+fn attribute(name: &str, value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    format!(" {}=\"{}\"", name, escaped)
+}
+
+fn fill_rule_name(fill_rule: FillRule) -> &'static str {
+    match fill_rule {
+        FillRule::NonZero => "nonzero",
+        FillRule::EvenOdd => "evenodd",
+    }
+}
+
+/// A number with at most `precision` decimals and no trailing zeros.
+fn format_number(n: f64, precision: usize) -> String {
+    let number = format!("{:.*}", precision, n);
+    let number = if number.contains('.') {
+        number.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &number
+    };
+
+    match number {
+        "-0" => "0".to_string(),
+        _ => number.to_string(),
+    }
+}
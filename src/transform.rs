@@ -0,0 +1,259 @@
+use crate::types::{
+    ArcResolution, CapStyle, HookRemoval, InputPoint, Nib, PenOrientation, Resampling,
+    SmootherType, StrokeOptions, StrokePoint, TaperOptions, TaperType,
+};
+use crate::vec::{dist, is_equal, uni};
+
+/// A 2D affine transform, to move, turn and resize strokes.
+///
+/// The matrix is `[a, b, c, d, e, f]`, as in SVG's `matrix(a b c d e f)`,
+/// which maps a point `[x, y]` to `[a * x + c * y + e, b * x + d * y + f]`.
+///
+/// Scaling the points of a stroke doesn't scale its width, so transform the
+/// options along with the points to keep the stroke looking the same, only
+/// bigger or smaller. A round tip can't be stretched further in one
+/// direction than another, so with a non-uniform scale the width is scaled by
+/// the average of the two scales (the square root of the change in area).
+/// To stretch a stroke exactly, transform its outline instead.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub matrix: [f64; 6],
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    /// The transform that leaves points where they are.
+    pub const IDENTITY: Self = Self {
+        matrix: [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+    };
+
+    pub fn from_matrix(matrix: [f64; 6]) -> Self {
+        Self { matrix }
+    }
+
+    pub fn translate(x: f64, y: f64) -> Self {
+        Self::from_matrix([1.0, 0.0, 0.0, 1.0, x, y])
+    }
+
+    /// Turn around the origin by an angle in radians, clockwise with the y
+    /// axis pointing down.
+    pub fn rotate(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::from_matrix([cos, sin, -sin, cos, 0.0, 0.0])
+    }
+
+    /// Turn around a point by an angle in radians.
+    pub fn rotate_around(angle: f64, center: [f64; 2]) -> Self {
+        Self::translate(-center[0], -center[1])
+            .then(Self::rotate(angle))
+            .then(Self::translate(center[0], center[1]))
+    }
+
+    /// Scale by the same amount in both directions, around the origin.
+    pub fn scale(scale: f64) -> Self {
+        Self::scale_xy(scale, scale)
+    }
+
+    /// Scale by different amounts across and down, around the origin.
+    pub fn scale_xy(x: f64, y: f64) -> Self {
+        Self::from_matrix([x, 0.0, 0.0, y, 0.0, 0.0])
+    }
+
+    /// This transform followed by another.
+    pub fn then(self, next: Self) -> Self {
+        let [a, b, c, d, e, f] = self.matrix;
+        let [na, nb, nc, nd, ne, nf] = next.matrix;
+        Self::from_matrix([
+            na * a + nc * b,
+            nb * a + nd * b,
+            na * c + nc * d,
+            nb * c + nd * d,
+            na * e + nc * f + ne,
+            nb * e + nd * f + nf,
+        ])
+    }
+
+    /// Move a point.
+    pub fn apply(&self, point: [f64; 2]) -> [f64; 2] {
+        let [x, y] = self.apply_vector(point);
+        [x + self.matrix[4], y + self.matrix[5]]
+    }
+
+    /// Turn and scale a direction or offset, without moving it.
+    pub fn apply_vector(&self, vector: [f64; 2]) -> [f64; 2] {
+        let [a, b, c, d, ..] = self.matrix;
+        [a * vector[0] + c * vector[1], b * vector[0] + d * vector[1]]
+    }
+
+    /// How much the width of a stroke is scaled by.
+    pub fn width_scale(&self) -> f64 {
+        let [a, b, c, d, ..] = self.matrix;
+        (a * d - b * c).abs().sqrt()
+    }
+
+    /// Move input points, turning the direction the pen leans in along with them.
+    pub fn transform_points(&self, points: &[InputPoint]) -> Vec<InputPoint> {
+        points
+            .iter()
+            .map(|point| match *point {
+                InputPoint::Array(xy, pressure) => InputPoint::Array(self.apply(xy), pressure),
+                InputPoint::Struct {
+                    x,
+                    y,
+                    pressure,
+                    timestamp,
+                    orientation,
+                } => {
+                    let [x, y] = self.apply([x, y]);
+                    InputPoint::Struct {
+                        x,
+                        y,
+                        pressure,
+                        timestamp,
+                        orientation: orientation.map(|o| self.transform_orientation(o)),
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Move the points from `get_stroke_points`, with their distances and
+    /// running lengths measured again.
+    pub fn transform_stroke_points(&self, points: &[StrokePoint]) -> Vec<StrokePoint> {
+        let mut transformed: Vec<StrokePoint> = Vec::with_capacity(points.len());
+
+        for (i, point) in points.iter().enumerate() {
+            let moved = self.apply(point.point);
+            let vector = self.apply_vector(point.vector);
+
+            let (distance, running_length) = match transformed.last() {
+                None => (0.0, point.running_length * self.width_scale()),
+                Some(prev) => {
+                    let distance = dist(prev.point, moved);
+                    // The running length can include points that were left
+                    // out, so scale its steps rather than adding up distances
+                    let step = point.running_length - points[i - 1].running_length;
+                    let ratio = if point.distance > 0.0 {
+                        distance / point.distance
+                    } else {
+                        self.width_scale()
+                    };
+                    (distance, prev.running_length + step * ratio)
+                }
+            };
+
+            transformed.push(StrokePoint {
+                point: moved,
+                distance,
+                vector: if is_equal(vector, [0.0, 0.0]) {
+                    vector
+                } else {
+                    uni(vector)
+                },
+                running_length,
+                orientation: point.orientation.map(|o| self.transform_orientation(o)),
+                ..point.clone()
+            });
+        }
+
+        transformed
+    }
+
+    /// Move the points of an outline, such as one from `get_stroke`.
+    pub fn transform_outline(&self, outline: &[[f64; 2]]) -> Vec<[f64; 2]> {
+        outline.iter().map(|&point| self.apply(point)).collect()
+    }
+
+    /// Scale the stroke's size, the lengths of its tapers and the distances
+    /// its smoother, resampling and hook removal work with to go with the
+    /// transformed points, and turn a fixed nib along with them.
+    pub fn transform_options(&self, options: &StrokeOptions) -> StrokeOptions {
+        let scale = self.width_scale();
+
+        StrokeOptions {
+            size: Some(options.size.unwrap_or(16.0) * scale),
+            smoother: options.smoother.map(|smoother| match smoother {
+                SmootherType::OneEuro { min_cutoff, beta } => SmootherType::OneEuro {
+                    min_cutoff,
+                    beta: if scale > 0.0 { beta / scale } else { beta },
+                },
+                SmootherType::Kalman {
+                    process_noise,
+                    measurement_noise,
+                } => SmootherType::Kalman {
+                    process_noise: process_noise * scale * scale,
+                    measurement_noise: measurement_noise * scale * scale,
+                },
+                SmootherType::LazyBrush { radius } => SmootherType::LazyBrush {
+                    radius: radius * scale,
+                },
+                smoother => smoother,
+            }),
+            resampling: options.resampling.map(|resampling| match resampling {
+                Resampling::Uniform { spacing } => Resampling::Uniform {
+                    spacing: spacing * scale,
+                },
+                Resampling::Adaptive {
+                    tolerance,
+                    max_spacing,
+                } => Resampling::Adaptive {
+                    tolerance: tolerance * scale,
+                    max_spacing: max_spacing * scale,
+                },
+            }),
+            hook_removal: options.hook_removal.map(|hook_removal| HookRemoval {
+                max_length: Some(hook_removal.max_length.unwrap_or(10.0) * scale),
+                ..hook_removal
+            }),
+            nib: options.nib.map(|nib| match nib {
+                Nib::Fixed { angle, aspect } => Nib::Fixed {
+                    angle: self.transform_angle(angle),
+                    aspect,
+                },
+                nib => nib,
+            }),
+            start: options.start.as_ref().map(|taper| scale_taper(taper, scale)),
+            end: options.end.as_ref().map(|taper| scale_taper(taper, scale)),
+            ..options.clone()
+        }
+    }
+
+    /// The angle a direction at `angle` points in once it's transformed.
+    fn transform_angle(&self, angle: f64) -> f64 {
+        let (sin, cos) = angle.sin_cos();
+        let [x, y] = self.apply_vector([cos, sin]);
+        y.atan2(x)
+    }
+
+    /// Turn the direction a pen leans in. How far it leans stays the same.
+    fn transform_orientation(&self, orientation: PenOrientation) -> PenOrientation {
+        PenOrientation {
+            azimuth: orientation
+                .azimuth_angle()
+                .map(|azimuth| self.transform_angle(azimuth)),
+            ..orientation
+        }
+    }
+}
+
+/// Taper options with their lengths scaled.
+fn scale_taper(taper: &TaperOptions, scale: f64) -> TaperOptions {
+    TaperOptions {
+        cap_style: taper.cap_style.map(|cap_style| match cap_style {
+            CapStyle::Round(ArcResolution::Tolerance(tolerance)) => {
+                CapStyle::Round(ArcResolution::Tolerance(tolerance * scale))
+            }
+            cap_style => cap_style,
+        }),
+        taper: taper.taper.as_ref().map(|taper| match *taper {
+            TaperType::Number(length) => TaperType::Number(length * scale),
+            TaperType::Bool(is_tapered) => TaperType::Bool(is_tapered),
+        }),
+        ..taper.clone()
+    }
+}
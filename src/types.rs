@@ -1,25 +1,43 @@
+use crate::smoother::Smoother;
+
 /// The options object for `get_stroke` or `get_stroke_points`.
 ///
 /// * `size` - The base size (diameter) of the stroke.
 /// * `thinning` - The effect of pressure on the stroke's size.
 /// * `smoothing` - How much to soften the stroke's edges.
+/// * `streamline` - How much to streamline the stroke.
+/// * `smoother` - The filter that smooths the input points. Defaults to `Streamline`.
+/// * `resampling` - How to space out the input points before they're smoothed.
+/// * `hook_removal` - Whether to trim the hook a stylus can leave as it lifts, once the stroke is complete.
 /// * `easing` - An easing function to apply to each point's pressure.
 /// * `simulate_pressure` - Whether to simulate pressure based on velocity.
+/// * `pressure_simulation` - How the speed is measured when simulating pressure.
+/// * `nib` - The shape of the pen tip.
+/// * `join` - How the outline is joined around sharp corners.
 /// * `start` - Cap, taper and easing for the start of the line.
 /// * `end` - Cap, taper and easing for the end of the line.
 /// * `last` - Whether to handle the points as a completed stroke.
+/// * `backfill` - Whether to keep the points at the start of the line that come before it reaches its minimum length.
 /// * `closed` - Whether to close the path by connecting the last point back to the first.
+///   For a line that loops back on itself without caps, use `get_closed_stroke`.
 #[derive(Clone)]
 pub struct StrokeOptions {
     pub size: Option<f64>,
     pub thinning: Option<f64>,
     pub smoothing: Option<f64>,
     pub streamline: Option<f64>,
+    pub smoother: Option<SmootherType>,
+    pub resampling: Option<Resampling>,
+    pub hook_removal: Option<HookRemoval>,
     pub easing: Option<fn(f64) -> f64>,
     pub simulate_pressure: Option<bool>,
+    pub pressure_simulation: Option<PressureSimulation>,
+    pub nib: Option<Nib>,
+    pub join: Option<JoinStyle>,
     pub start: Option<TaperOptions>,
     pub end: Option<TaperOptions>,
     pub last: Option<bool>,
+    pub backfill: Option<bool>,
     pub closed: Option<bool>,
 }
 
@@ -30,30 +48,259 @@ impl Default for StrokeOptions {
             thinning: None,
             smoothing: None,
             streamline: None,
+            smoother: None,
+            resampling: None,
+            hook_removal: None,
             easing: None,
             simulate_pressure: None,
+            pressure_simulation: None,
+            nib: None,
+            join: None,
             start: None,
             end: None,
             last: None,
+            backfill: None,
             closed: Some(false),
         }
     }
 }
 
 /// Options for tapering at the start or end of a stroke
-#[derive(Clone)]
+///
+/// * `cap` - Whether to draw a cap.
+/// * `cap_style` - The shape of the cap.
+/// * `taper` - The distance to taper.
+/// * `easing` - An easing function for the taper.
+#[derive(Clone, Default)]
 pub struct TaperOptions {
     pub cap: Option<bool>,
+    pub cap_style: Option<CapStyle>,
     pub taper: Option<TaperType>,
     pub easing: Option<fn(f64) -> f64>,
 }
 
-impl Default for TaperOptions {
+/// The filter that smooths a stroke's input points, to hide the shakes of
+/// the hand and the noise of the device.
+///
+/// Times are in milliseconds, and points without timestamps are taken to be
+/// a frame (1/60 of a second) apart. Closed loops are always smoothed with
+/// `Streamline`.
+#[derive(Clone, Copy, Default)]
+pub enum SmootherType {
+    /// Move part of the way toward each new point, as set by `streamline`.
+    /// Less streamline lags less but shakes more
+    #[default]
+    Streamline,
+    /// Move toward the pen at a rate set by how long it takes to get most of
+    /// the way there, whatever the pen's sample rate
+    Exponential { time_constant: f64 },
+    /// Smooth slow movements heavily and fast ones hardly at all. Try a
+    /// `min_cutoff` of 1 hertz and a `beta` of 0.01, and raise `beta` for
+    /// less lag or lower `min_cutoff` for less shaking
+    OneEuro { min_cutoff: f64, beta: f64 },
+    /// Follow the pen's position and velocity. `process_noise` is how much
+    /// the pen's speed changes from frame to frame, and `measurement_noise`
+    /// how far off each point is, both as squared distances
+    Kalman {
+        process_noise: f64,
+        measurement_noise: f64,
+    },
+    /// Pull the line along behind the pen on a string `radius` units long
+    LazyBrush { radius: f64 },
+    /// A smoother of your own, made fresh for each stroke
+    Custom(fn() -> Box<dyn Smoother>),
+}
+
+impl std::fmt::Debug for SmootherType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            SmootherType::Streamline => write!(f, "Streamline"),
+            SmootherType::Exponential { time_constant } => f
+                .debug_struct("Exponential")
+                .field("time_constant", &time_constant)
+                .finish(),
+            SmootherType::OneEuro { min_cutoff, beta } => f
+                .debug_struct("OneEuro")
+                .field("min_cutoff", &min_cutoff)
+                .field("beta", &beta)
+                .finish(),
+            SmootherType::Kalman {
+                process_noise,
+                measurement_noise,
+            } => f
+                .debug_struct("Kalman")
+                .field("process_noise", &process_noise)
+                .field("measurement_noise", &measurement_noise)
+                .finish(),
+            SmootherType::LazyBrush { radius } => {
+                f.debug_struct("LazyBrush").field("radius", &radius).finish()
+            }
+            SmootherType::Custom(_) => write!(f, "Custom(Fn)"),
+        }
+    }
+}
+
+/// How to space out a stroke's input points along the line through them, so
+/// that the stroke doesn't depend on how often the device samples the pen.
+/// Distances are in the same units as the points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resampling {
+    /// Put the points `spacing` apart
+    Uniform { spacing: f64 },
+    /// Put the points closer together where the line bends, so that it
+    /// strays no more than about `tolerance` from the curve through them, and
+    /// no more than `max_spacing` apart where it's straight
+    Adaptive { tolerance: f64, max_spacing: f64 },
+}
+
+/// How `simplify_points` and `simplify_outline` choose the points to drop.
+/// Either way, no dropped point ends up further than `tolerance` from the
+/// simplified line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Simplification {
+    /// Ramer–Douglas–Peucker: keep the point furthest from the line between
+    /// the points either side of it, until every point is within `tolerance`.
+    /// Fast, but tends to leave spiky corners
+    RamerDouglasPeucker { tolerance: f64 },
+    /// Visvalingam–Whyatt: drop the point that makes the smallest triangle
+    /// with its neighbours, for as long as doing so keeps within `tolerance`.
+    /// Slower, but keeps the overall shape more smoothly
+    VisvalingamWhyatt { tolerance: f64 },
+}
+
+/// Thresholds for finding the hook a stylus can leave at the end of a stroke
+/// as it lifts, as used by `find_end_hook` and the `hook_removal` option.
+///
+/// * `max_length` - How far back from the end of the stroke a hook can start. Defaults to 10.
+/// * `min_angle` - How sharply the line must turn where the hook starts, in radians. Defaults to π/3.
+/// * `min_pressure_drop` - How much the pressure must fall along the hook. Defaults to 0.1.
+///   With 0, a sharp turn alone is taken to be a hook.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HookRemoval {
+    pub max_length: Option<f64>,
+    pub min_angle: Option<f64>,
+    pub min_pressure_drop: Option<f64>,
+}
+
+/// Options for `predict_points`.
+///
+/// * `model` - How to extrapolate the pen's movement. Defaults to `ConstantVelocity`.
+/// * `latency` - How far ahead to predict, in milliseconds. Defaults to two frames.
+/// * `count` - How many points to predict, evenly spaced in time. Defaults to 3.
+#[derive(Debug, Clone, Default)]
+pub struct PredictionOptions {
+    pub model: Option<PredictionModel>,
+    pub latency: Option<f64>,
+    pub count: Option<usize>,
+}
+
+/// How `predict_points` extrapolates the pen's movement from the latest
+/// input points. Points without timestamps are taken to be a frame apart.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PredictionModel {
+    /// Carry on at the speed between the last two points
+    #[default]
+    ConstantVelocity,
+    /// Carry on speeding up or slowing down as over the last three points.
+    /// Follows curves more closely, but overshoots more when the pen stops
+    ConstantAcceleration,
+    /// Fit a curve through the last point that passes as close as it can to
+    /// the `history` points before it, which evens out noise
+    CurveFit { history: usize },
+}
+
+/// How the speed of the pen is measured when simulating pressure
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PressureSimulation {
+    /// Use the distance between points, so faster devices give thicker lines
+    #[default]
+    Distance,
+    /// Use the distance over the time between points, so the line doesn't depend
+    /// on the sample rate. Points without increasing timestamps fall back to `Distance`.
+    Velocity,
+}
+
+/// The shape of the pen tip, which sets how far the outline is offset from
+/// the line in each direction
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Nib {
+    /// A circular tip, so the line is as thick in every direction
+    #[default]
+    Round,
+    /// A broad-edge tip held at a fixed `angle` (in radians), like a calligraphy pen.
+    /// The pen's twist, if there is one, turns the tip further. `aspect` is the
+    /// tip's thickness as a share of its width, from 0 (a flat edge) to 1 (round).
+    Fixed { angle: f64, aspect: f64 },
+    /// A tip that stretches along the pen's azimuth as it tilts, down to an
+    /// `aspect` of its width when the pen lies flat. Points without tilt data
+    /// are drawn with a round tip.
+    Tilt { aspect: f64 },
+}
+
+/// How the outer side of the outline is joined where the line turns a sharp corner
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum JoinStyle {
+    /// An arc around the corner
+    #[default]
+    Round,
+    /// A straight edge across the corner
+    Bevel,
+    /// The outer edges extended until they meet, or a bevel if they would meet
+    /// more than `limit` times the radius away from the corner
+    Miter { limit: f64 },
+}
+
+/// The shape of the cap at the start or end of a stroke
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CapStyle {
+    /// A half circle around the end point
+    Round(ArcResolution),
+    /// A square end that reaches past the end point by the stroke's radius
+    Square,
+    /// A flat end at the end point
+    Butt,
+}
+
+impl Default for CapStyle {
     fn default() -> Self {
-        Self {
-            cap: None,
-            taper: None,
-            easing: None,
+        CapStyle::Round(ArcResolution::default())
+    }
+}
+
+/// How finely a curved cap is split into straight segments
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArcResolution {
+    /// A fixed number of segments for each half circle
+    Segments(usize),
+    /// As many segments as needed to keep the cap within this distance of a
+    /// true arc, so that larger strokes get smoother caps
+    Tolerance(f64),
+}
+
+impl Default for ArcResolution {
+    fn default() -> Self {
+        ArcResolution::Segments(4)
+    }
+}
+
+impl ArcResolution {
+    /// The number of segments for an arc with the given radius and angle (in radians)
+    pub fn segments(&self, radius: f64, angle: f64) -> usize {
+        let half_circles = angle.abs() / std::f64::consts::PI;
+
+        match *self {
+            ArcResolution::Segments(segments) => {
+                f64::max(1.0, (segments as f64 * half_circles).ceil()) as usize
+            }
+            ArcResolution::Tolerance(tolerance) => {
+                if tolerance <= 0.0 || radius <= 0.0 {
+                    return 1;
+                }
+
+                // The widest angle whose chord stays within the tolerance of the arc
+                let step = 2.0 * f64::max(-1.0, 1.0 - tolerance / radius).acos();
+                f64::max(1.0, (angle.abs() / step).ceil()) as usize
+            }
         }
     }
 }
@@ -72,11 +319,18 @@ impl std::fmt::Debug for StrokeOptions {
             .field("thinning", &self.thinning)
             .field("smoothing", &self.smoothing)
             .field("streamline", &self.streamline)
+            .field("smoother", &self.smoother)
+            .field("resampling", &self.resampling)
+            .field("hook_removal", &self.hook_removal)
             .field("easing", &if self.easing.is_some() { "Fn" } else { "None" })
             .field("simulate_pressure", &self.simulate_pressure)
+            .field("pressure_simulation", &self.pressure_simulation)
+            .field("nib", &self.nib)
+            .field("join", &self.join)
             .field("start", &self.start)
             .field("end", &self.end)
             .field("last", &self.last)
+            .field("backfill", &self.backfill)
             .field("closed", &self.closed)
             .finish()
     }
@@ -86,12 +340,298 @@ impl std::fmt::Debug for TaperOptions {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TaperOptions")
             .field("cap", &self.cap)
+            .field("cap_style", &self.cap_style)
             .field("taper", &self.taper)
             .field("easing", &if self.easing.is_some() { "Fn" } else { "None" })
             .finish()
     }
 }
 
+/// The parts of a stroke's outline, as returned by `get_stroke_outline`.
+///
+/// `left` and `right` run from the start of the stroke to its end, and
+/// `left_indices` and `right_indices` give the index of the stroke point
+/// that each of their points came from. The start cap runs from the left
+/// side around to the right side, and the end cap from the right side
+/// around to the left side.
+#[derive(Debug, Clone, Default)]
+pub struct StrokeOutline {
+    pub left: Vec<[f64; 2]>,
+    pub left_indices: Vec<usize>,
+    pub right: Vec<[f64; 2]>,
+    pub right_indices: Vec<usize>,
+    pub start_cap: Vec<[f64; 2]>,
+    pub end_cap: Vec<[f64; 2]>,
+}
+
+/// The shape of a stroke that hit tests are made against.
+#[derive(Debug, Clone, Copy)]
+pub enum StrokeShape<'a> {
+    /// The outline polygon from `get_stroke` or `get_stroke_outline_points`,
+    /// filled with the nonzero rule, along with the stroke points it was made
+    /// from. This is the exact shape that's drawn.
+    Outline {
+        points: &'a [StrokePoint],
+        outline: &'a [[f64; 2]],
+    },
+    /// The line through the stroke points, with the stroke's radius at each
+    /// point as it's drawn with the options. This skips building the outline,
+    /// and treats every tip as round.
+    Centerline {
+        points: &'a [StrokePoint],
+        options: &'a StrokeOptions,
+    },
+}
+
+/// The part of a stroke nearest to a point, as found by `distance_to_stroke`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrokeHit {
+    /// The index of the nearest stroke point.
+    pub index: usize,
+    /// How far along the stroke the nearest point on its centerline is.
+    pub running_length: f64,
+    /// The distance from the point to the edge of the stroke, which is
+    /// negative inside the stroke.
+    pub distance: f64,
+}
+
+/// How much of a stroke is inside a lasso.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Containment {
+    /// At least the threshold's share of the stroke is inside
+    Inside,
+    /// Some of the stroke is inside, but less than the threshold, or the
+    /// lasso is inside the stroke's outline
+    Partial,
+    /// None of the stroke is inside
+    Outside,
+}
+
+/// Where a stroke is relative to a lasso, as returned by `select_with_lasso`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LassoSelection {
+    pub containment: Containment,
+    /// The share of the stroke that's inside the lasso, from 0 to 1.
+    pub coverage: f64,
+}
+
+/// An eraser dragged across strokes, as used by `erase_stroke`.
+///
+/// # Fields
+/// - `path`: The points the eraser's center moved through
+/// - `radius`: The radius of the eraser
+/// - `cut_ends`: The cap and taper for the ends where strokes are cut. Cut
+///   ends are flat (with a `Butt` cap and no taper) by default
+#[derive(Debug, Clone, Default)]
+pub struct Eraser {
+    pub path: Vec<[f64; 2]>,
+    pub radius: f64,
+    pub cut_ends: Option<TaperOptions>,
+}
+
+/// Part of a stroke that's left after erasing, as returned by `erase_stroke`.
+///
+/// The options are the stroke's own, with `cut_ends` in place of the `start`
+/// or `end` options at the ends that were cut. Pass them to `get_stroke`
+/// along with the points.
+#[derive(Debug, Clone)]
+pub struct StrokePiece {
+    pub points: Vec<InputPoint>,
+    pub options: StrokeOptions,
+    /// Whether the piece starts where the eraser cut the stroke.
+    pub is_start_cut: bool,
+    /// Whether the piece ends where the eraser cut the stroke.
+    pub is_end_cut: bool,
+}
+
+/// A rectangle that contains a shape, from its smallest to its largest coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: [f64; 2],
+    pub max: [f64; 2],
+}
+
+impl Bounds {
+    /// The bounds of some points, or `None` if there are none.
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a [f64; 2]>) -> Option<Self> {
+        points.into_iter().fold(None, |bounds, &point| match bounds {
+            None => Some(Self {
+                min: point,
+                max: point,
+            }),
+            Some(bounds) => Some(bounds.including(point)),
+        })
+    }
+
+    /// These bounds grown to include a point.
+    pub fn including(self, point: [f64; 2]) -> Self {
+        Self {
+            min: [self.min[0].min(point[0]), self.min[1].min(point[1])],
+            max: [self.max[0].max(point[0]), self.max[1].max(point[1])],
+        }
+    }
+
+    /// The bounds that contain both these bounds and another.
+    pub fn union(self, other: Self) -> Self {
+        self.including(other.min).including(other.max)
+    }
+
+    /// Whether these bounds and another overlap or touch.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min[0] <= other.max[0]
+            && other.min[0] <= self.max[0]
+            && self.min[1] <= other.max[1]
+            && other.min[1] <= self.max[1]
+    }
+
+    /// Whether another bounds is wholly inside these.
+    pub fn contains(&self, other: &Self) -> bool {
+        self.min[0] <= other.min[0]
+            && self.min[1] <= other.min[1]
+            && other.max[0] <= self.max[0]
+            && other.max[1] <= self.max[1]
+    }
+
+    /// These bounds grown by `amount` on every side.
+    pub fn expand(self, amount: f64) -> Self {
+        Self {
+            min: [self.min[0] - amount, self.min[1] - amount],
+            max: [self.max[0] + amount, self.max[1] + amount],
+        }
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max[0] - self.min[0]
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max[1] - self.min[1]
+    }
+}
+
+/// A rectangle that contains a shape, turned to fit it as tightly as possible.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrientedBounds {
+    pub center: [f64; 2],
+    /// The width along the rectangle's turned x axis, and the height across it.
+    pub size: [f64; 2],
+    /// The angle of the rectangle's x axis, in radians.
+    pub angle: f64,
+}
+
+impl OrientedBounds {
+    /// The rectangle's corners, in order around it.
+    pub fn corners(&self) -> [[f64; 2]; 4] {
+        let (sin, cos) = self.angle.sin_cos();
+        let [w, h] = [self.size[0] / 2.0, self.size[1] / 2.0];
+        [[-w, -h], [w, -h], [w, h], [-w, h]].map(|[x, y]| {
+            [
+                self.center[0] + x * cos - y * sin,
+                self.center[1] + x * sin + y * cos,
+            ]
+        })
+    }
+}
+
+/// Measurements of a stroke, as returned by `get_stroke_metrics`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrokeMetrics {
+    /// The bounds of the stroke's outline.
+    pub bounds: Bounds,
+    /// The smallest turned rectangle that contains the stroke's outline.
+    pub oriented_bounds: OrientedBounds,
+    /// The length of the stroke's centerline.
+    pub length: f64,
+    /// The area the stroke fills, counting overlaps once.
+    pub area: f64,
+    /// The center of the area the stroke fills.
+    pub centroid: [f64; 2],
+    /// The smallest radius of the stroke, after tapering.
+    pub min_radius: f64,
+    /// The largest radius of the stroke, after tapering.
+    pub max_radius: f64,
+    /// The stroke's radius averaged along its centerline.
+    pub average_radius: f64,
+}
+
+/// The rule that decides which parts of a path with overlapping or nested
+/// subpaths are filled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FillRule {
+    #[default]
+    NonZero,
+    EvenOdd,
+}
+
+/// How `SvgPath` writes the curves of a path.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SvgCurve {
+    /// Quadratic curves, with `T` commands where a curve continues smoothly
+    /// from the previous one. This is the smallest output.
+    #[default]
+    Smooth,
+    /// Quadratic curves, each with its own `Q` command.
+    Quadratic,
+    /// Cubic curves, with `C` commands.
+    Cubic,
+    /// Straight `L` lines that stay within `tolerance` of the curves.
+    Polyline { tolerance: f64 },
+}
+
+/// Options for writing SVG path data with `SvgPath`.
+///
+/// # Fields
+/// * `precision` - The number of decimals to write, or 0 for whole numbers. Defaults to 2.
+/// * `relative` - Whether to write commands relative to the current point.
+/// * `minify` - Whether to drop trailing zeros and any separators that aren't needed.
+/// * `curve` - How to write the curves.
+#[derive(Debug, Clone, Default)]
+pub struct SvgPathOptions {
+    pub precision: Option<usize>,
+    pub relative: Option<bool>,
+    pub minify: Option<bool>,
+    pub curve: Option<SvgCurve>,
+}
+
+/// A simple polygon, as returned by `get_polygons_from_stroke`: an outer ring
+/// running counter-clockwise and any holes in it running clockwise (with the
+/// y axis pointing up). The rings end with their first point.
+#[derive(Debug, Clone, Default)]
+pub struct Polygon {
+    pub outer: Vec<[f64; 2]>,
+    pub holes: Vec<Vec<[f64; 2]>>,
+}
+
+/// A vertex of the mesh returned by `get_stroke_mesh`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshVertex {
+    pub position: [f64; 2],
+    /// How far along the stroke the vertex is, from 0 at the start to 1 at the end.
+    pub distance: f64,
+    /// Where the vertex is across the stroke, from -1 on the left side
+    /// through 0 on the line to 1 on the right side.
+    pub across: f64,
+}
+
+/// A triangle mesh covering a stroke, as returned by `get_stroke_mesh`.
+/// Every three `indices` are the vertices of one triangle.
+#[derive(Debug, Clone, Default)]
+pub struct StrokeMesh {
+    pub vertices: Vec<MeshVertex>,
+    pub indices: Vec<u32>,
+}
+
+/// The outline of a closed loop, as returned by `get_closed_stroke`.
+///
+/// The outer ring winds counter-clockwise and the inner ring clockwise
+/// (with the y axis pointing up), so the space inside the loop is left
+/// empty with either fill rule. Both rings end with their first point.
+#[derive(Debug, Clone, Default)]
+pub struct ClosedStroke {
+    pub outer: Vec<[f64; 2]>,
+    pub inner: Vec<[f64; 2]>,
+}
+
 /// The points returned by `get_stroke_points`, and the input for `get_stroke_outline_points`.
 #[derive(Debug, Clone)]
 pub struct StrokePoint {
@@ -100,11 +640,127 @@ pub struct StrokePoint {
     pub distance: f64,
     pub vector: [f64; 2],
     pub running_length: f64,
+    pub timestamp: Option<f64>,
+    pub orientation: Option<PenOrientation>,
+}
+
+/// The orientation of a stylus, using the same units as pointer events.
+///
+/// * `tilt_x` - The angle between the pen and the Y-Z plane, in degrees (-90 to 90).
+/// * `tilt_y` - The angle between the pen and the X-Z plane, in degrees (-90 to 90).
+/// * `azimuth` - The direction the pen leans in, in radians clockwise from the X axis.
+/// * `twist` - The pen's rotation around its own axis, in degrees (0 to 359).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PenOrientation {
+    pub tilt_x: Option<f64>,
+    pub tilt_y: Option<f64>,
+    pub azimuth: Option<f64>,
+    pub twist: Option<f64>,
+}
+
+impl PenOrientation {
+    /// The direction the pen leans in, in radians, from `azimuth` or else from the tilt.
+    pub fn azimuth_angle(&self) -> Option<f64> {
+        match (self.azimuth, self.tilt_x, self.tilt_y) {
+            (Some(azimuth), _, _) => Some(azimuth),
+            (None, Some(tilt_x), Some(tilt_y)) if tilt_x != 0.0 || tilt_y != 0.0 => Some(
+                f64::atan2(tilt_y.to_radians().tan(), tilt_x.to_radians().tan()),
+            ),
+            _ => None,
+        }
+    }
+
+    /// How far the pen leans from upright, in radians (0 to PI / 2).
+    pub fn tilt_angle(&self) -> Option<f64> {
+        if self.tilt_x.is_none() && self.tilt_y.is_none() {
+            return None;
+        }
+
+        let tan_x = self.tilt_x.unwrap_or(0.0).to_radians().tan();
+        let tan_y = self.tilt_y.unwrap_or(0.0).to_radians().tan();
+        Some((tan_x * tan_x + tan_y * tan_y).sqrt().atan())
+    }
 }
 
-/// Represents an input point with optional pressure
+/// Represents an input point with optional pressure, timestamp (in milliseconds)
+/// and pen orientation
 #[derive(Debug, Clone)]
 pub enum InputPoint {
     Array([f64; 2], Option<f64>),
-    Struct { x: f64, y: f64, pressure: Option<f64> },
+    Struct {
+        x: f64,
+        y: f64,
+        pressure: Option<f64>,
+        timestamp: Option<f64>,
+        orientation: Option<PenOrientation>,
+    },
+}
+
+impl InputPoint {
+    /// A point with optional pressure, and no timestamp or orientation
+    pub fn new(x: f64, y: f64, pressure: Option<f64>) -> Self {
+        InputPoint::Struct {
+            x,
+            y,
+            pressure,
+            timestamp: None,
+            orientation: None,
+        }
+    }
+
+    /// The same point, at the given time in milliseconds
+    pub fn with_timestamp(self, timestamp: f64) -> Self {
+        let [x, y] = self.point();
+        InputPoint::Struct {
+            x,
+            y,
+            pressure: self.pressure(),
+            timestamp: Some(timestamp),
+            orientation: self.orientation(),
+        }
+    }
+
+    /// The same point, drawn with the pen held at the given orientation
+    pub fn with_orientation(self, orientation: PenOrientation) -> Self {
+        let [x, y] = self.point();
+        InputPoint::Struct {
+            x,
+            y,
+            pressure: self.pressure(),
+            timestamp: self.timestamp(),
+            orientation: Some(orientation),
+        }
+    }
+
+    /// The point as `[x, y]`
+    pub fn point(&self) -> [f64; 2] {
+        match self {
+            InputPoint::Array(point, _) => *point,
+            InputPoint::Struct { x, y, .. } => [*x, *y],
+        }
+    }
+
+    /// The pressure, if there is one
+    pub fn pressure(&self) -> Option<f64> {
+        match self {
+            InputPoint::Array(_, pressure) => *pressure,
+            InputPoint::Struct { pressure, .. } => *pressure,
+        }
+    }
+
+    /// The timestamp, if there is one
+    pub fn timestamp(&self) -> Option<f64> {
+        match self {
+            InputPoint::Array(..) => None,
+            InputPoint::Struct { timestamp, .. } => *timestamp,
+        }
+    }
+
+    /// The pen orientation, if there is one
+    pub fn orientation(&self) -> Option<PenOrientation> {
+        match self {
+            InputPoint::Array(..) => None,
+            InputPoint::Struct { orientation, .. } => *orientation,
+        }
+    }
 } 
\ No newline at end of file
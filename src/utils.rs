@@ -1,7 +1,7 @@
-/// Calculates the average of two numbers
-fn average(a: f64, b: f64) -> f64 {
-    (a + b) / 2.0
-}
+use crate::path::draw_stroke;
+use crate::polygon::resolve_overlaps;
+use crate::svg::SvgPath;
+use crate::types::{Polygon, SvgPathOptions};
 
 /// Converts a stroke's outline points to an SVG path data string
 ///
@@ -12,40 +12,43 @@ fn average(a: f64, b: f64) -> f64 {
 /// # Returns
 /// A string containing SVG path commands
 pub fn get_svg_path_from_stroke(points: &[[f64; 2]], closed: bool) -> String {
-    let len = points.len();
-
-    if len < 4 {
-        return String::new();
-    }
-
-    let a = points[0];
-    let b = points[1];
-    let c = points[2];
-
-    // Format with exactly 2 decimal places for consistent output
-    let mut result = format!(
-        "M{:.2},{:.2} Q{:.2},{:.2} {:.2},{:.2} T",
-        a[0],
-        a[1],
-        b[0],
-        b[1],
-        average(b[0], c[0]),
-        average(b[1], c[1])
-    );
-
-    for i in 2..(len - 1) {
-        let a = points[i];
-        let b = points[i + 1];
-        result.push_str(&format!(
-            "{:.2},{:.2} ",
-            average(a[0], b[0]),
-            average(a[1], b[1])
-        ));
-    }
+    get_svg_path_from_stroke_with_options(points, closed, &SvgPathOptions::default())
+}
 
-    if closed {
-        result.push('Z');
-    }
+/// Converts a stroke's outline points to an SVG path data string, written
+/// with the given precision, commands and curves
+///
+/// # Arguments
+/// * `points` - The outline points returned by `get_stroke`
+/// * `closed` - Whether to close the path with a 'Z' command
+/// * `options` - How to write the path data
+///
+/// # Returns
+/// A string containing SVG path commands
+pub fn get_svg_path_from_stroke_with_options(
+    points: &[[f64; 2]],
+    closed: bool,
+    options: &SvgPathOptions,
+) -> String {
+    let mut path = SvgPath::with_options(options.clone());
+    draw_stroke(points, closed, &mut path);
+    path.into_string()
+}
 
-    result
-} 
\ No newline at end of file
+/// Resolves a stroke's outline into simple polygons that don't cross over
+/// themselves or each other.
+///
+/// Where a stroke crosses itself, the outline from `get_stroke` overlaps,
+/// which leaves holes with the even-odd fill rule and makes translucent
+/// strokes darker at the overlaps. The polygons cover the area that the
+/// outline covers with the nonzero fill rule, and fill the same way with
+/// either rule.
+///
+/// # Arguments
+/// * `points` - The outline points returned by `get_stroke`
+///
+/// # Returns
+/// The polygons, each with an outer ring and any holes in it
+pub fn get_polygons_from_stroke(points: &[[f64; 2]]) -> Vec<Polygon> {
+    resolve_overlaps(points)
+}
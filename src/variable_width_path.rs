@@ -0,0 +1,353 @@
+use crate::get_stroke_outline_points::{get_outline_points_from_radii, get_stroke_radii};
+use crate::get_stroke_points::set_first_vector;
+use crate::path::PathSink;
+use crate::types::{StrokeOptions, StrokePoint};
+use crate::vec::{add, dist, dpr, is_equal, len, lrp, mul, neg, sub, uni};
+
+// How many times to improve the fit of a curve before splitting it
+const MAX_ITERATIONS: usize = 4;
+
+/// A point that a `VariableWidthPath` runs through, with the radius of the
+/// stroke there.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathKnot {
+    pub point: [f64; 2],
+    pub radius: f64,
+}
+
+/// A stroke's centerline as a few cubic Bezier curves, with the stroke's
+/// radius at each knot.
+///
+/// It's much smaller than the stroke points it was fitted to, and can be
+/// edited and then turned back into an outline with `to_outline`. Between two
+/// knots, the radius changes evenly along the curve.
+///
+/// # Fields
+/// - `knots`: The points the path runs through, from the start of the stroke
+/// - `controls`: The two control points of the curve from each knot to the
+///   next, so there's one pair fewer than there are knots
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VariableWidthPath {
+    pub knots: Vec<PathKnot>,
+    pub controls: Vec<[[f64; 2]; 2]>,
+}
+
+impl VariableWidthPath {
+    /// Fit a path to the points from `get_stroke_points`, so that neither
+    /// the points nor their radii are more than `tolerance` away from it.
+    ///
+    /// Each knot is one of the stroke points, with the radius that
+    /// `get_stroke_radius` gives it using the same options. The path only
+    /// turns sharply where the stroke points do.
+    pub fn fit(points: &[StrokePoint], options: &StrokeOptions, tolerance: f64) -> Self {
+        let tolerance = tolerance.max(1e-6);
+        let radii = get_stroke_radii(points, options);
+
+        let mut samples: Vec<PathKnot> = Vec::with_capacity(points.len());
+        for (point, radius) in points.iter().zip(radii) {
+            if samples.last().map_or(true, |prev| !is_equal(prev.point, point.point)) {
+                samples.push(PathKnot {
+                    point: point.point,
+                    radius,
+                });
+            }
+        }
+
+        let mut path = Self::default();
+        let Some(&first) = samples.first() else {
+            return path;
+        };
+        path.knots.push(first);
+
+        // Fit the curves between sharp corners separately, so that the
+        // corners stay sharp
+        let mut start = 0;
+        for i in 1..samples.len() {
+            let is_corner = i + 1 < samples.len() && {
+                let incoming = sub(samples[i].point, samples[i - 1].point);
+                let outgoing = sub(samples[i + 1].point, samples[i].point);
+                dpr(incoming, outgoing) < 0.0
+            };
+
+            if is_corner || i == samples.len() - 1 {
+                let run = &samples[start..=i];
+                let start_tangent = uni(sub(run[1].point, run[0].point));
+                let end_tangent = uni(sub(run[run.len() - 2].point, run[run.len() - 1].point));
+                path.fit_curve(run, start_tangent, end_tangent, tolerance);
+                start = i;
+            }
+        }
+
+        path
+    }
+
+    /// The outline around the path, as `get_stroke` would draw it with the
+    /// same options. Tapers and caps come from the options, and the pressure
+    /// is taken from the knots' radii.
+    pub fn to_outline(&self, options: &StrokeOptions) -> Vec<[f64; 2]> {
+        // Points about a quarter of the stroke's size apart
+        let spacing = f64::max(0.25, options.size.unwrap_or(16.0) / 4.0);
+        get_outline_points_from_radii(&self.stroke_points(spacing), options)
+    }
+
+    /// Draw the centerline, as one move and a cubic curve for each segment.
+    pub fn draw<S: PathSink + ?Sized>(&self, sink: &mut S) {
+        let Some(first) = self.knots.first() else {
+            return;
+        };
+
+        sink.move_to(first.point);
+        for (controls, knot) in self.controls.iter().zip(&self.knots[1..]) {
+            sink.cubic_to(controls[0], controls[1], knot.point);
+        }
+    }
+
+    /// The curve from knot `i` to the next.
+    fn segment(&self, i: usize) -> [[f64; 2]; 4] {
+        let [control1, control2] = self.controls[i];
+        [self.knots[i].point, control1, control2, self.knots[i + 1].point]
+    }
+
+    /// Points along the path, no more than `spacing` apart, with the radius
+    /// at each point in place of its pressure.
+    fn stroke_points(&self, spacing: f64) -> Vec<StrokePoint> {
+        let mut points: Vec<StrokePoint> = Vec::new();
+        let mut running_length = 0.0;
+
+        let mut add_point = |point: [f64; 2], radius: f64, points: &mut Vec<StrokePoint>| {
+            let (vector, distance) = match points.last() {
+                Some(prev) if is_equal(prev.point, point) => return,
+                Some(prev) => (uni(sub(prev.point, point)), dist(prev.point, point)),
+                None => ([1.0, 1.0], 0.0),
+            };
+            running_length += distance;
+
+            points.push(StrokePoint {
+                point,
+                pressure: radius,
+                distance,
+                vector,
+                running_length,
+                timestamp: None,
+                orientation: None,
+            });
+        };
+
+        if let Some(first) = self.knots.first() {
+            add_point(first.point, first.radius, &mut points);
+        }
+
+        for i in 0..self.controls.len().min(self.knots.len().saturating_sub(1)) {
+            let curve = self.segment(i);
+
+            // The control polygon is at least as long as the curve
+            let length: f64 = curve.windows(2).map(|pair| dist(pair[0], pair[1])).sum();
+            let steps = (length / spacing).ceil().clamp(1.0, 10_000.0) as usize;
+
+            let (start_radius, end_radius) = (self.knots[i].radius, self.knots[i + 1].radius);
+            for step in 1..=steps {
+                let t = step as f64 / steps as f64;
+                let radius = start_radius + (end_radius - start_radius) * t;
+                add_point(bezier(curve, t), radius, &mut points);
+            }
+        }
+
+        set_first_vector(&mut points);
+        points
+    }
+
+    /// Fit one or more curves to the points, which start at the last knot, and
+    /// add them. The tangents point from each end into the curve.
+    fn fit_curve(
+        &mut self,
+        points: &[PathKnot],
+        start_tangent: [f64; 2],
+        end_tangent: [f64; 2],
+        tolerance: f64,
+    ) {
+        let first = points[0].point;
+        let last = points[points.len() - 1].point;
+
+        if points.len() == 2 {
+            let reach = dist(first, last) / 3.0;
+            self.add_curve(
+                [add(first, mul(start_tangent, reach)), add(last, mul(end_tangent, reach))],
+                points[1],
+            );
+            return;
+        }
+
+        let mut params = chord_length_params(points);
+        let mut curve = generate_bezier(points, &params, start_tangent, end_tangent);
+        let (mut error, mut split) = max_error(points, &params, curve);
+
+        // When the curve is close, move the points to where they're closest
+        // to it and fit it again
+        if error > tolerance && error <= tolerance * 4.0 {
+            for _ in 0..MAX_ITERATIONS {
+                params = reparameterize(points, &params, curve);
+                curve = generate_bezier(points, &params, start_tangent, end_tangent);
+                (error, split) = max_error(points, &params, curve);
+
+                if error <= tolerance {
+                    break;
+                }
+            }
+        }
+
+        if error <= tolerance {
+            self.add_curve([curve[1], curve[2]], points[points.len() - 1]);
+            return;
+        }
+
+        // Split the points where they're furthest from the curve, and fit
+        // each half with the same tangent where they meet
+        let center = sub(points[split - 1].point, points[split + 1].point);
+        let center = if len(center) > 0.0 {
+            uni(center)
+        } else {
+            uni(sub(points[split - 1].point, points[split].point))
+        };
+
+        self.fit_curve(&points[..=split], start_tangent, center, tolerance);
+        self.fit_curve(&points[split..], neg(center), end_tangent, tolerance);
+    }
+
+    fn add_curve(&mut self, controls: [[f64; 2]; 2], end: PathKnot) {
+        self.controls.push(controls);
+        self.knots.push(end);
+    }
+}
+
+/// A point on a cubic Bezier curve.
+fn bezier(curve: [[f64; 2]; 4], t: f64) -> [f64; 2] {
+    let a = lrp(lrp(curve[0], curve[1], t), lrp(curve[1], curve[2], t), t);
+    let b = lrp(lrp(curve[1], curve[2], t), lrp(curve[2], curve[3], t), t);
+    lrp(a, b, t)
+}
+
+/// The first and second derivatives of a cubic Bezier curve.
+fn bezier_derivatives(curve: [[f64; 2]; 4], t: f64) -> ([f64; 2], [f64; 2]) {
+    let d = [
+        mul(sub(curve[1], curve[0]), 3.0),
+        mul(sub(curve[2], curve[1]), 3.0),
+        mul(sub(curve[3], curve[2]), 3.0),
+    ];
+    let first = lrp(lrp(d[0], d[1], t), lrp(d[1], d[2], t), t);
+    let second = lrp(mul(sub(d[1], d[0]), 2.0), mul(sub(d[2], d[1]), 2.0), t);
+    (first, second)
+}
+
+/// How far along the points each point is, from 0 to 1, by distance.
+fn chord_length_params(points: &[PathKnot]) -> Vec<f64> {
+    let mut params = Vec::with_capacity(points.len());
+    let mut total = 0.0;
+    params.push(0.0);
+    for pair in points.windows(2) {
+        total += dist(pair[0].point, pair[1].point);
+        params.push(total);
+    }
+
+    params.iter().map(|param| param / total).collect()
+}
+
+/// The curve with the given tangents that's closest to the points, found by
+/// least squares.
+fn generate_bezier(
+    points: &[PathKnot],
+    params: &[f64],
+    start_tangent: [f64; 2],
+    end_tangent: [f64; 2],
+) -> [[f64; 2]; 4] {
+    let first = points[0].point;
+    let last = points[points.len() - 1].point;
+
+    let mut c = [[0.0; 2]; 2];
+    let mut x = [0.0; 2];
+    for (point, &t) in points.iter().zip(params) {
+        let s = 1.0 - t;
+        let b = [s * s * s, 3.0 * s * s * t, 3.0 * s * t * t, t * t * t];
+        let a = [mul(start_tangent, b[1]), mul(end_tangent, b[2])];
+
+        c[0][0] += dpr(a[0], a[0]);
+        c[0][1] += dpr(a[0], a[1]);
+        c[1][1] += dpr(a[1], a[1]);
+
+        let on_line = add(mul(first, b[0] + b[1]), mul(last, b[2] + b[3]));
+        let rest = sub(point.point, on_line);
+        x[0] += dpr(a[0], rest);
+        x[1] += dpr(a[1], rest);
+    }
+    c[1][0] = c[0][1];
+
+    let det_c = c[0][0] * c[1][1] - c[1][0] * c[0][1];
+    let (alpha_start, alpha_end) = if det_c.abs() > f64::EPSILON {
+        (
+            (x[0] * c[1][1] - x[1] * c[0][1]) / det_c,
+            (c[0][0] * x[1] - c[1][0] * x[0]) / det_c,
+        )
+    } else {
+        (0.0, 0.0)
+    };
+
+    // Fall back to a third of the way along the line between the ends when the
+    // fit is degenerate or turns the curve back on itself
+    let length = dist(first, last);
+    let epsilon = 1e-6 * length;
+    let (alpha_start, alpha_end) = if alpha_start < epsilon || alpha_end < epsilon {
+        (length / 3.0, length / 3.0)
+    } else {
+        (alpha_start, alpha_end)
+    };
+
+    [
+        first,
+        add(first, mul(start_tangent, alpha_start)),
+        add(last, mul(end_tangent, alpha_end)),
+        last,
+    ]
+}
+
+/// The furthest any point or radius is from the curve, and the index of
+/// that point. The radius changes evenly along the curve.
+fn max_error(points: &[PathKnot], params: &[f64], curve: [[f64; 2]; 4]) -> (f64, usize) {
+    let start_radius = points[0].radius;
+    let end_radius = points[points.len() - 1].radius;
+
+    let mut max = 0.0;
+    let mut split = points.len() / 2;
+    for (i, (point, &t)) in points.iter().zip(params).enumerate().skip(1) {
+        if i == points.len() - 1 {
+            break;
+        }
+
+        let radius = start_radius + (end_radius - start_radius) * t;
+        let error = f64::max(dist(bezier(curve, t), point.point), (radius - point.radius).abs());
+        if error > max {
+            max = error;
+            split = i;
+        }
+    }
+
+    (max, split)
+}
+
+/// Move each point's parameter to where the curve is closest to it, with a
+/// step of Newton's method.
+fn reparameterize(points: &[PathKnot], params: &[f64], curve: [[f64; 2]; 4]) -> Vec<f64> {
+    points
+        .iter()
+        .zip(params)
+        .map(|(point, &t)| {
+            let offset = sub(bezier(curve, t), point.point);
+            let (first, second) = bezier_derivatives(curve, t);
+            let denominator = dpr(first, first) + dpr(offset, second);
+
+            if denominator.abs() <= f64::EPSILON {
+                t
+            } else {
+                (t - dpr(offset, first) / denominator).clamp(0.0, 1.0)
+            }
+        })
+        .collect()
+}
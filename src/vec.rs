@@ -33,6 +33,11 @@ pub fn dpr(a: [f64; 2], b: [f64; 2]) -> f64 {
     a[0] * b[0] + a[1] * b[1]
 }
 
+/// Cross product (the z component of the 3D cross product)
+pub fn cpr(a: [f64; 2], b: [f64; 2]) -> f64 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
 /// Get whether two vectors are equal.
 pub fn is_equal(a: [f64; 2], b: [f64; 2]) -> bool {
     a[0] == b[0] && a[1] == b[1]
@@ -64,7 +69,6 @@ pub fn dist(a: [f64; 2], b: [f64; 2]) -> f64 {
 }
 
 /// Mean between two vectors or mid vector between two vectors
-#[allow(dead_code)]
 pub fn med(a: [f64; 2], b: [f64; 2]) -> [f64; 2] {
     mul(add(a, b), 0.5)
 }
@@ -88,6 +92,16 @@ pub fn lrp(a: [f64; 2], b: [f64; 2], t: f64) -> [f64; 2] {
     add(a, mul(sub(b, a), t))
 }
 
+/// How far along the segment from A to B the point nearest to P is, from 0 to 1
+pub fn nearest_t(p: [f64; 2], a: [f64; 2], b: [f64; 2]) -> f64 {
+    let length = len2(sub(b, a));
+    if length == 0.0 {
+        return 0.0;
+    }
+
+    (dpr(sub(p, a), sub(b, a)) / length).clamp(0.0, 1.0)
+}
+
 /// Project a point A in the direction B by a scalar c
 #[allow(dead_code)]
 pub fn prj(a: [f64; 2], b: [f64; 2], c: f64) -> [f64; 2] {
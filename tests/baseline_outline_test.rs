@@ -0,0 +1,54 @@
+use freedraw::{get_stroke, InputPoint, StrokeOptions};
+use serde_json::Value;
+use std::fs::File;
+use std::io::Read;
+
+fn load_json(filename: &str) -> Value {
+    let mut file = File::open(format!("tests/{}", filename)).expect("Could not open file");
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).expect("Could not read file");
+    serde_json::from_str(&contents).expect("Could not parse JSON")
+}
+
+fn convert_json_to_input_points(points_json: &Value) -> Vec<InputPoint> {
+    points_json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|point| match point.as_array() {
+            Some(coords) => InputPoint::Array(
+                [coords[0].as_f64().unwrap(), coords[1].as_f64().unwrap()],
+                coords.get(2).and_then(|p| p.as_f64()),
+            ),
+            None => InputPoint::Array(
+                [point["x"].as_f64().unwrap(), point["y"].as_f64().unwrap()],
+                point.get("pressure").and_then(|p| p.as_f64()),
+            ),
+        })
+        .collect()
+}
+
+#[test]
+fn test_default_outlines_are_unchanged() {
+    // The outlines drawn with the default options before any were added
+    let baseline = load_json("baseline_outlines.json");
+    let inputs = load_json("inputs.json");
+    let flash = load_json("flash.json");
+
+    for (name, expected) in baseline.as_object().unwrap() {
+        let points = match name.as_str() {
+            "flash" => &flash,
+            name => &inputs[name],
+        };
+        let outline = get_stroke(&convert_json_to_input_points(points), &StrokeOptions::default());
+
+        // The same points, give or take a rounding error
+        let expected: Vec<[f64; 2]> = serde_json::from_value(expected.clone()).unwrap();
+        assert_eq!(outline.len(), expected.len(), "{}", name);
+        for (point, expected) in outline.iter().zip(&expected) {
+            let is_close = (point[0] - expected[0]).abs() < 1e-9
+                && (point[1] - expected[1]).abs() < 1e-9;
+            assert!(is_close, "{}: {:?} {:?}", name, point, expected);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use freedraw::{
+    draw_stroke, get_svg_path_from_stroke, get_svg_path_from_stroke_with_options, PathSink,
+    SvgCurve, SvgPath, SvgPathOptions,
+};
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Move([f64; 2]),
+    Line([f64; 2]),
+    Quad([f64; 2], [f64; 2]),
+    Cubic([f64; 2], [f64; 2], [f64; 2]),
+    Close,
+}
+
+#[derive(Default)]
+struct Recorder(Vec<Command>);
+
+impl PathSink for Recorder {
+    fn move_to(&mut self, point: [f64; 2]) {
+        self.0.push(Command::Move(point));
+    }
+
+    fn line_to(&mut self, point: [f64; 2]) {
+        self.0.push(Command::Line(point));
+    }
+
+    fn quad_to(&mut self, control: [f64; 2], point: [f64; 2]) {
+        self.0.push(Command::Quad(control, point));
+    }
+
+    fn cubic_to(&mut self, control1: [f64; 2], control2: [f64; 2], point: [f64; 2]) {
+        self.0.push(Command::Cubic(control1, control2, point));
+    }
+
+    fn close(&mut self) {
+        self.0.push(Command::Close);
+    }
+}
+
+#[test]
+fn test_draw_stroke_commands() {
+    let points = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]];
+
+    let mut recorder = Recorder::default();
+    draw_stroke(&points, true, &mut recorder);
+
+    // The curve runs through the midpoints, with the points as control points
+    assert_eq!(
+        recorder.0,
+        vec![
+            Command::Move([0.0, 0.0]),
+            Command::Quad([10.0, 0.0], [10.0, 5.0]),
+            Command::Quad([10.0, 10.0], [5.0, 10.0]),
+            Command::Quad([0.0, 10.0], [0.0, 5.0]),
+            Command::Close,
+        ]
+    );
+
+    // Too few points to draw a curve
+    let mut recorder = Recorder::default();
+    draw_stroke(&points[..3], true, &mut recorder);
+    assert!(recorder.0.is_empty());
+}
+
+#[test]
+fn test_svg_path_sink() {
+    let points = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]];
+
+    let mut path = SvgPath::new();
+    draw_stroke(&points, true, &mut path);
+    assert_eq!(path.as_str(), get_svg_path_from_stroke(&points, true));
+    assert_eq!(
+        path.into_string(),
+        "M0.00,0.00 Q10.00,0.00 10.00,5.00 T5.00,10.00 0.00,5.00 Z"
+    );
+
+    let mut path = SvgPath::new();
+    path.move_to([1.0, 2.0]);
+    path.line_to([3.0, 4.0]);
+    path.cubic_to([5.0, 6.0], [7.0, 8.0], [9.0, 10.0]);
+    path.close();
+    assert_eq!(
+        path.into_string(),
+        "M1.00,2.00 L3.00,4.00 C5.00,6.00 7.00,8.00 9.00,10.00Z"
+    );
+}
+
+#[test]
+fn test_default_svg_path_is_unchanged() {
+    let points: Vec<[f64; 2]> = (0..12)
+        .map(|i| {
+            let angle = i as f64 * 0.537;
+            [122.987 + 40.3 * angle.cos(), 91.393 + 25.7 * angle.sin()]
+        })
+        .collect();
+
+    // Without options, each point is rounded on its own, as it always was
+    assert_eq!(
+        get_svg_path_from_stroke(&points, false),
+        "M163.29,91.39 Q157.61,104.54 149.90,109.26 T131.78,115.53 111.18,115.00 93.91,107.83 \
+         84.82,96.03 86.47,82.92 98.41,72.20 117.26,66.88 137.73,68.47 154.04,76.50 "
+    );
+}
+
+#[test]
+fn test_svg_path_options() {
+    let points = [[0.0, 0.0], [10.5, 0.25], [10.0, 10.0], [-0.5, 10.0], [0.0, 0.0]];
+    let path = |options: SvgPathOptions| get_svg_path_from_stroke_with_options(&points, true, &options);
+
+    assert_eq!(
+        path(SvgPathOptions {
+            precision: Some(1),
+            relative: Some(true),
+            ..Default::default()
+        }),
+        "m0.0,0.0 q10.5,0.2 10.2,5.1 t-5.5,4.9 -4.9,-5.0 z"
+    );
+    assert_eq!(
+        path(SvgPathOptions {
+            precision: Some(1),
+            relative: Some(true),
+            minify: Some(true),
+            ..Default::default()
+        }),
+        "m0 0q10.5.2 10.2 5.1t-5.5 4.9-4.9-5z"
+    );
+    assert_eq!(
+        path(SvgPathOptions {
+            precision: Some(0),
+            minify: Some(true),
+            curve: Some(SvgCurve::Quadratic),
+            ..Default::default()
+        }),
+        "M0 0Q10 0 10 5 10 10 5 10 0 10 0 5Z"
+    );
+
+    let cubic = path(SvgPathOptions {
+        curve: Some(SvgCurve::Cubic),
+        ..Default::default()
+    });
+    assert_eq!(cubic.matches('C').count(), 3);
+
+    let polyline = path(SvgPathOptions {
+        curve: Some(SvgCurve::Polyline { tolerance: 0.01 }),
+        ..Default::default()
+    });
+    assert!(!polyline.contains('Q') && !polyline.contains('T'));
+    assert!(polyline.matches(" L").count() > 3 * 8);
+}
+
+/// Read the numbers from minified path data with absolute commands.
+fn parse_path(data: &str) -> Vec<(char, Vec<f64>)> {
+    let mut commands: Vec<(char, Vec<f64>)> = Vec::new();
+    let mut number = String::new();
+    let end_number = |number: &mut String, commands: &mut Vec<(char, Vec<f64>)>| {
+        if !number.is_empty() {
+            commands.last_mut().unwrap().1.push(number.parse().unwrap());
+            number.clear();
+        }
+    };
+
+    for c in data.chars() {
+        if c.is_ascii_alphabetic() {
+            end_number(&mut number, &mut commands);
+            commands.push((c, Vec::new()));
+        } else if c == ' ' || c == '-' || (c == '.' && number.contains('.')) {
+            end_number(&mut number, &mut commands);
+            if c != ' ' {
+                number.push(c);
+            }
+        } else {
+            number.push(c);
+        }
+    }
+    end_number(&mut number, &mut commands);
+
+    commands
+}
+
+#[test]
+fn test_smooth_curves_stay_close_when_rounded() {
+    // The outline of a circle, as one long run of smooth curves
+    let points: Vec<[f64; 2]> = (0..=200)
+        .map(|i| {
+            let angle = i as f64 / 200.0 * std::f64::consts::TAU;
+            [100.3 * angle.cos(), 100.3 * angle.sin()]
+        })
+        .collect();
+
+    let mut recorder = Recorder::default();
+    draw_stroke(&points, false, &mut recorder);
+
+    let options = SvgPathOptions {
+        precision: Some(0),
+        minify: Some(true),
+        ..Default::default()
+    };
+    let data = get_svg_path_from_stroke_with_options(&points, false, &options);
+    assert!(data.matches('T').count() > 0);
+
+    // Follow the curves the way a reader would, reflecting the control points
+    let mut written = Vec::new();
+    let mut current = [0.0, 0.0];
+    let mut control = [0.0, 0.0];
+    for (command, numbers) in parse_path(&data) {
+        for n in numbers.chunks(if command == 'Q' { 4 } else { 2 }) {
+            match command {
+                'M' => current = [n[0], n[1]],
+                'Q' => {
+                    control = [n[0], n[1]];
+                    current = [n[2], n[3]];
+                    written.push((control, current));
+                }
+                'T' => {
+                    control = [2.0 * current[0] - control[0], 2.0 * current[1] - control[1]];
+                    current = [n[0], n[1]];
+                    written.push((control, current));
+                }
+                _ => panic!("unexpected command {}", command),
+            }
+        }
+    }
+
+    let curves: Vec<([f64; 2], [f64; 2])> = recorder
+        .0
+        .iter()
+        .filter_map(|command| match command {
+            Command::Quad(control, point) => Some((*control, *point)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(written.len(), curves.len());
+
+    // Every point stays within one unit, however long the run
+    let is_close = |a: [f64; 2], b: [f64; 2]| (a[0] - b[0]).abs() <= 1.0 && (a[1] - b[1]).abs() <= 1.0;
+    for ((control, point), (expected_control, expected_point)) in written.iter().zip(&curves) {
+        assert!(is_close(*control, *expected_control));
+        assert!(is_close(*point, *expected_point));
+    }
+}
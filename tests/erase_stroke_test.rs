@@ -0,0 +1,139 @@
+use freedraw::{
+    erase_stroke, get_stroke, Bounds, CapStyle, Eraser, InputPoint, StrokeOptions, TaperOptions,
+    TaperType,
+};
+
+/// A straight line along y = 50 from x = 0 to 200, with the pressure rising
+/// from 0 to 1.
+fn line() -> Vec<InputPoint> {
+    (0..=20)
+        .map(|i| {
+            let x = i as f64 * 10.0;
+            InputPoint::Array([x, 50.0], Some(x / 200.0))
+        })
+        .collect()
+}
+
+#[test]
+fn test_erase_middle_of_stroke() {
+    let options = StrokeOptions {
+        size: Some(10.0),
+        ..Default::default()
+    };
+    let eraser = Eraser {
+        path: vec![[95.0, 0.0], [95.0, 100.0]],
+        radius: 20.0,
+        ..Default::default()
+    };
+
+    let pieces = erase_stroke(&line(), &options, &eraser);
+    assert_eq!(pieces.len(), 2);
+
+    let (first, second) = (&pieces[0], &pieces[1]);
+    assert!(!first.is_start_cut && first.is_end_cut);
+    assert!(second.is_start_cut && !second.is_end_cut);
+
+    // The cuts are at the edge of the eraser, with the pressure in between
+    let cut = first.points.last().unwrap();
+    assert!((cut.point()[0] - 75.0).abs() < 1e-9);
+    assert!((cut.pressure().unwrap() - 0.375).abs() < 1e-9);
+    let cut = &second.points[0];
+    assert!((cut.point()[0] - 115.0).abs() < 1e-9);
+    assert!((cut.pressure().unwrap() - 0.575).abs() < 1e-9);
+
+    // The original points are kept between the cuts
+    assert_eq!(first.points.len(), 9);
+    assert_eq!(first.points[3].point(), [30.0, 50.0]);
+
+    // Each piece's outline ends flat at the edge of the eraser
+    let first_bounds = Bounds::from_points(&get_stroke(&first.points, &first.options)).unwrap();
+    assert!((first_bounds.max[0] - 75.0).abs() < 0.01, "{:?}", first_bounds);
+    assert!(first_bounds.min[0] < 0.0);
+    let second_bounds = Bounds::from_points(&get_stroke(&second.points, &second.options)).unwrap();
+    assert!((second_bounds.min[0] - 115.0).abs() < 0.01, "{:?}", second_bounds);
+    assert!(second_bounds.max[0] > 200.0);
+}
+
+#[test]
+fn test_erase_with_cut_ends() {
+    let options = StrokeOptions {
+        size: Some(10.0),
+        end: Some(TaperOptions {
+            cap_style: Some(CapStyle::Square),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let eraser = Eraser {
+        // A dot at the start of the line
+        path: vec![[0.0, 50.0]],
+        radius: 25.0,
+        cut_ends: Some(TaperOptions {
+            taper: Some(TaperType::Number(20.0)),
+            ..Default::default()
+        }),
+    };
+
+    let pieces = erase_stroke(&line(), &options, &eraser);
+    assert_eq!(pieces.len(), 1);
+
+    // The cut start gets the eraser's taper, and the end keeps its own cap
+    let piece = &pieces[0];
+    assert!(piece.is_start_cut && !piece.is_end_cut);
+    assert!((piece.points[0].point()[0] - 25.0).abs() < 1e-9);
+    let start = piece.options.start.as_ref().unwrap();
+    assert!(matches!(start.taper, Some(TaperType::Number(n)) if n == 20.0));
+    let end = piece.options.end.as_ref().unwrap();
+    assert_eq!(end.cap_style, Some(CapStyle::Square));
+}
+
+#[test]
+fn test_erase_many_cuts() {
+    let options = StrokeOptions::default();
+
+    // A zigzag crossing y = 50 six times
+    let zigzag: Vec<InputPoint> = (0..=6)
+        .map(|i| InputPoint::Struct {
+            x: i as f64 * 20.0,
+            y: if i % 2 == 0 { 0.0 } else { 100.0 },
+            pressure: Some(0.5),
+            timestamp: Some(i as f64 * 16.0),
+            orientation: None,
+        })
+        .collect();
+    let eraser = Eraser {
+        path: vec![[-50.0, 50.0], [200.0, 50.0]],
+        radius: 5.0,
+        ..Default::default()
+    };
+
+    let pieces = erase_stroke(&zigzag, &options, &eraser);
+    assert_eq!(pieces.len(), 7);
+    for piece in &pieces {
+        for point in &piece.points {
+            assert!((point.point()[1] - 50.0).abs() >= 5.0 - 1e-9);
+        }
+    }
+
+    // The timestamps at the cuts are in between
+    let cut = pieces[0].points.last().unwrap();
+    assert!((cut.timestamp().unwrap() - 7.2).abs() < 1e-9);
+
+    // Missing the stroke keeps it whole, and covering it erases it
+    let miss = Eraser {
+        path: vec![[500.0, 500.0]],
+        radius: 10.0,
+        ..Default::default()
+    };
+    let pieces = erase_stroke(&zigzag, &options, &miss);
+    assert_eq!(pieces.len(), 1);
+    assert_eq!(pieces[0].points.len(), zigzag.len());
+    assert!(!pieces[0].is_start_cut && !pieces[0].is_end_cut);
+
+    let cover = Eraser {
+        path: vec![[60.0, 50.0]],
+        radius: 200.0,
+        ..Default::default()
+    };
+    assert!(erase_stroke(&zigzag, &options, &cover).is_empty());
+}
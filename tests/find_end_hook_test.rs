@@ -0,0 +1,116 @@
+use freedraw::{
+    find_end_hook, get_stroke, HookRemoval, InputPoint, StrokeBuilder, StrokeOptions,
+};
+
+/// A line along y = 0 from x = 0 to 100, pressed firmly, that flicks back
+/// and down as the pen lifts.
+fn hooked_line() -> Vec<InputPoint> {
+    let mut points: Vec<InputPoint> = (0..=50)
+        .map(|i| InputPoint::Array([i as f64 * 2.0, 0.0], Some(0.6)))
+        .collect();
+    points.push(InputPoint::Array([99.0, 2.0], Some(0.45)));
+    points.push(InputPoint::Array([97.5, 4.0], Some(0.3)));
+    points.push(InputPoint::Array([96.0, 5.5], Some(0.15)));
+    points
+}
+
+fn without_pressure(points: &[InputPoint]) -> Vec<InputPoint> {
+    points
+        .iter()
+        .map(|point| InputPoint::Array(point.point(), None))
+        .collect()
+}
+
+#[test]
+fn test_find_end_hook() {
+    let points = hooked_line();
+    let hook_removal = HookRemoval::default();
+    assert_eq!(find_end_hook(&points, &hook_removal), Some(51));
+
+    // Without a pressure drop it's a turn the pen meant to make...
+    let bare = without_pressure(&points);
+    assert_eq!(find_end_hook(&bare, &hook_removal), None);
+
+    // ...unless the turn alone is enough
+    let turn_only = HookRemoval {
+        min_pressure_drop: Some(0.0),
+        ..Default::default()
+    };
+    assert_eq!(find_end_hook(&bare, &turn_only), Some(51));
+
+    // A hook longer than the maximum length isn't one
+    let shorter = HookRemoval {
+        max_length: Some(3.0),
+        ..Default::default()
+    };
+    assert_eq!(find_end_hook(&points, &shorter), None);
+
+    // Nor is a gentle turn
+    let sharper = HookRemoval {
+        min_angle: Some(2.8),
+        ..Default::default()
+    };
+    assert_eq!(find_end_hook(&points, &sharper), None);
+
+    // A tick is left alone, as the line before the turn is shorter than it
+    let tick: Vec<InputPoint> = [
+        [0.0, 0.0, 0.6],
+        [2.0, 2.0, 0.6],
+        [5.0, -1.0, 0.4],
+        [8.0, -4.0, 0.2],
+    ]
+    .iter()
+    .map(|&[x, y, pressure]| InputPoint::Array([x, y], Some(pressure)))
+    .collect();
+    assert_eq!(find_end_hook(&tick, &hook_removal), None);
+}
+
+#[test]
+fn test_hook_removal_option() {
+    let points = hooked_line();
+    let options = StrokeOptions {
+        size: Some(8.0),
+        simulate_pressure: Some(false),
+        hook_removal: Some(HookRemoval::default()),
+        ..Default::default()
+    };
+    let plain = StrokeOptions {
+        hook_removal: None,
+        ..options.clone()
+    };
+
+    // Only a completed stroke is trimmed
+    assert_eq!(get_stroke(&points, &options), get_stroke(&points, &plain));
+
+    let last = StrokeOptions {
+        last: Some(true),
+        ..options.clone()
+    };
+    let plain_last = StrokeOptions {
+        last: Some(true),
+        ..plain
+    };
+    assert_eq!(get_stroke(&points, &last), get_stroke(&points[..51], &plain_last));
+
+    // The builder trims the hook when the stroke is finished
+    let mut builder = StrokeBuilder::new(options);
+    for point in &points {
+        builder.push(point.clone());
+    }
+    let delta = builder.finish();
+    assert_eq!(delta.left_start, 0);
+    assert_eq!(builder.outline(), get_stroke(&points, &last));
+    assert_eq!(builder.input_points().len(), points.len());
+
+    // With `last` set throughout, each update is trimmed, and the deltas
+    // still add up to the builder's outline
+    let mut builder = StrokeBuilder::new(last.clone());
+    let mut left: Vec<[f64; 2]> = Vec::new();
+    for (i, point) in points.iter().enumerate() {
+        let delta = builder.push(point.clone());
+        left.truncate(delta.left_start);
+        left.extend(delta.left);
+        assert_eq!(left, builder.left());
+        assert_eq!(builder.outline(), get_stroke(&points[..=i], &last));
+    }
+}
@@ -0,0 +1,79 @@
+use freedraw::{get_closed_stroke, get_stroke, InputPoint, StrokeOptions};
+use std::f64::consts::TAU;
+
+fn circle(start: usize, count: usize, radius: f64) -> Vec<InputPoint> {
+    (0..count)
+        .map(|i| {
+            let angle = ((start + i) % count) as f64 / count as f64 * TAU;
+            InputPoint::Array([radius * angle.cos(), radius * angle.sin()], None)
+        })
+        .collect()
+}
+
+fn signed_area(ring: &[[f64; 2]]) -> f64 {
+    ring.windows(2)
+        .map(|w| w[0][0] * w[1][1] - w[1][0] * w[0][1])
+        .sum::<f64>()
+        / 2.0
+}
+
+fn distance_from_origin(point: &[f64; 2]) -> f64 {
+    point[0].hypot(point[1])
+}
+
+#[test]
+fn test_closed_stroke_has_two_rings() {
+    let options = StrokeOptions {
+        size: Some(16.0),
+        thinning: Some(0.0),
+        ..Default::default()
+    };
+    let stroke = get_closed_stroke(&circle(0, 64, 100.0), &options);
+
+    assert!(stroke.outer.len() > 3);
+    assert!(stroke.inner.len() > 3);
+    assert_eq!(stroke.outer.first(), stroke.outer.last());
+    assert_eq!(stroke.inner.first(), stroke.inner.last());
+
+    // The rings wind in opposite directions
+    assert!(signed_area(&stroke.outer) > 0.0);
+    assert!(signed_area(&stroke.inner) < 0.0);
+
+    // Without caps, the rings stay the same distance from the line all round
+    for point in &stroke.outer {
+        let distance = distance_from_origin(point);
+        assert!(distance > 100.0 && distance < 110.0, "outer point at {}", distance);
+    }
+    for point in &stroke.inner {
+        let distance = distance_from_origin(point);
+        assert!(distance > 90.0 && distance < 100.0, "inner point at {}", distance);
+    }
+}
+
+#[test]
+fn test_closed_stroke_has_no_seam() {
+    let options = StrokeOptions::default();
+    let first = get_closed_stroke(&circle(0, 64, 100.0), &options);
+
+    // Starting the loop somewhere else gives the same shape
+    for start in [16, 40] {
+        let other = get_closed_stroke(&circle(start, 64, 100.0), &options);
+        let outer_change = signed_area(&other.outer) / signed_area(&first.outer);
+        let inner_change = signed_area(&other.inner) / signed_area(&first.inner);
+        assert!((outer_change - 1.0).abs() < 0.01);
+        assert!((inner_change - 1.0).abs() < 0.01);
+    }
+}
+
+#[test]
+fn test_closed_stroke_with_too_few_points() {
+    let options = StrokeOptions::default();
+    let points = vec![
+        InputPoint::Array([0.0, 0.0], None),
+        InputPoint::Array([10.0, 10.0], None),
+    ];
+
+    let stroke = get_closed_stroke(&points, &options);
+    assert_eq!(stroke.outer, get_stroke(&points, &options));
+    assert!(stroke.inner.is_empty());
+}
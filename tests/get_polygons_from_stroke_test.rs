@@ -0,0 +1,71 @@
+use freedraw::{get_polygons_from_stroke, get_stroke, InputPoint, StrokeOptions};
+
+fn signed_area(ring: &[[f64; 2]]) -> f64 {
+    ring.windows(2)
+        .map(|w| w[0][0] * w[1][1] - w[1][0] * w[0][1])
+        .sum::<f64>()
+        / 2.0
+}
+
+fn winding_number(point: [f64; 2], ring: &[[f64; 2]]) -> i32 {
+    let mut winding = 0;
+    for w in ring.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        let side = (b[0] - a[0]) * (point[1] - a[1]) - (point[0] - a[0]) * (b[1] - a[1]);
+        if a[1] <= point[1] {
+            if b[1] > point[1] && side > 0.0 {
+                winding += 1;
+            }
+        } else if b[1] <= point[1] && side < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding
+}
+
+#[test]
+fn test_polygons_from_a_bow_tie() {
+    // The two halves of a bow tie wind in opposite directions
+    let outline = vec![[0.0, 0.0], [2.0, 2.0], [2.0, 0.0], [0.0, 2.0], [0.0, 0.0]];
+    let polygons = get_polygons_from_stroke(&outline);
+
+    assert_eq!(polygons.len(), 2);
+    for polygon in &polygons {
+        assert!(polygon.holes.is_empty());
+        assert!((signed_area(&polygon.outer) - 1.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn test_polygons_from_a_looping_stroke() {
+    let points: Vec<InputPoint> = (0..200)
+        .map(|i| {
+            let t = i as f64 * 0.08;
+            InputPoint::Array([t * 30.0 + 60.0 * t.cos(), 60.0 * t.sin()], None)
+        })
+        .collect();
+    let outline = get_stroke(&points, &StrokeOptions::default());
+    let polygons = get_polygons_from_stroke(&outline);
+
+    // The stroke loops around three times, leaving holes inside the loops
+    assert_eq!(polygons.len(), 1);
+    assert_eq!(polygons[0].holes.len(), 3);
+    assert!(signed_area(&polygons[0].outer) > 0.0);
+    assert!(polygons[0].holes.iter().all(|hole| signed_area(hole) < 0.0));
+
+    let rings: Vec<&Vec<[f64; 2]>> = polygons
+        .iter()
+        .flat_map(|polygon| std::iter::once(&polygon.outer).chain(&polygon.holes))
+        .collect();
+
+    // Both fill rules fill the polygons where the outline is filled with the nonzero rule
+    for x in (-80..560).step_by(3) {
+        for y in (-80..80).step_by(3) {
+            let point = [x as f64 + 0.5, y as f64 + 0.5];
+            let is_filled = winding_number(point, &outline) != 0;
+            let winding: i32 = rings.iter().map(|ring| winding_number(point, ring)).sum();
+            assert_eq!(winding != 0, is_filled);
+            assert_eq!(winding % 2 != 0, is_filled);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use freedraw::{
+    get_stroke, get_stroke_mesh, get_stroke_points, CapStyle, InputPoint, StrokeOptions,
+    TaperOptions,
+};
+use serde_json::Value;
+use std::fs::File;
+use std::io::Read;
+
+fn load_test_data(filename: &str) -> serde_json::Value {
+    let mut file = File::open(format!("tests/{}", filename)).expect("Could not open file");
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).expect("Could not read file");
+    serde_json::from_str(&contents).expect("Could not parse JSON")
+}
+
+fn convert_json_to_input_points(points_json: &[Value]) -> Vec<InputPoint> {
+    points_json
+        .iter()
+        .map(|point| {
+            let coords = point.as_array().unwrap();
+            InputPoint::Array(
+                [coords[0].as_f64().unwrap(), coords[1].as_f64().unwrap()],
+                coords.get(2).and_then(|p| p.as_f64()),
+            )
+        })
+        .collect()
+}
+
+fn triangle_area(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    ((b[0] - a[0]) * (c[1] - a[1]) - (c[0] - a[0]) * (b[1] - a[1])) / 2.0
+}
+
+fn polygon_area(points: &[[f64; 2]]) -> f64 {
+    points
+        .windows(2)
+        .map(|w| w[0][0] * w[1][1] - w[1][0] * w[0][1])
+        .sum::<f64>()
+        / 2.0
+}
+
+#[test]
+fn test_stroke_mesh_is_valid() {
+    let corners = load_test_data("corners.json");
+    let input_points = convert_json_to_input_points(corners["corners"].as_array().unwrap());
+    let options = StrokeOptions::default();
+
+    let stroke_points = get_stroke_points(&input_points, &options);
+    let mesh = get_stroke_mesh(&stroke_points, &options);
+
+    assert!(!mesh.indices.is_empty());
+    assert_eq!(mesh.indices.len() % 3, 0);
+    assert!(mesh.indices.iter().all(|&i| (i as usize) < mesh.vertices.len()));
+
+    for vertex in &mesh.vertices {
+        assert!((0.0..=1.0).contains(&vertex.distance));
+        assert!((-1.0..=1.0).contains(&vertex.across));
+    }
+}
+
+#[test]
+fn test_stroke_mesh_covers_the_outline() {
+    let input_points: Vec<InputPoint> = (0..=20)
+        .map(|i| InputPoint::Array([i as f64 * 10.0, 0.0], None))
+        .collect();
+    // A round start cap, rather than the original one that turns back over
+    // the line and so overlaps it
+    let options = StrokeOptions {
+        thinning: Some(0.0),
+        start: Some(TaperOptions {
+            cap_style: Some(CapStyle::default()),
+            ..Default::default()
+        }),
+        last: Some(true),
+        ..Default::default()
+    };
+
+    let stroke_points = get_stroke_points(&input_points, &options);
+    let mesh = get_stroke_mesh(&stroke_points, &options);
+
+    // On a line that doesn't cross itself, the triangles fill the outline
+    // without overlapping
+    let areas: Vec<f64> = mesh
+        .indices
+        .chunks(3)
+        .map(|t| {
+            let [a, b, c] = [t[0], t[1], t[2]].map(|i| mesh.vertices[i as usize].position);
+            triangle_area(a, b, c)
+        })
+        .collect();
+
+    let mesh_area: f64 = areas.iter().map(|area| area.abs()).sum();
+    let outline_area = polygon_area(&get_stroke(&input_points, &options)).abs();
+    assert!((mesh_area - outline_area).abs() < 1e-4 * outline_area);
+    assert!((areas.iter().sum::<f64>().abs() - mesh_area).abs() < 1e-4 * outline_area);
+
+    // The sides run from the start to the end of the stroke
+    let first = mesh.vertices.iter().map(|v| v.distance).fold(f64::MAX, f64::min);
+    let last = mesh.vertices.iter().map(|v| v.distance).fold(f64::MIN, f64::max);
+    assert_eq!((first, last), (0.0, 1.0));
+}
+
+#[test]
+fn test_stroke_mesh_for_one_point() {
+    let input_points = vec![InputPoint::Array([1.0, 1.0], None)];
+    let options = StrokeOptions::default();
+
+    let stroke_points = get_stroke_points(&input_points, &options);
+    let mesh = get_stroke_mesh(&stroke_points, &options);
+    assert!(!mesh.indices.is_empty());
+}
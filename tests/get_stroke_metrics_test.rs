@@ -0,0 +1,109 @@
+use freedraw::{
+    get_stroke_metrics, get_stroke_points, CapStyle, InputPoint, StrokeOptions, TaperOptions,
+    TaperType,
+};
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+/// A straight line from one point to another, with the same pressure all along.
+fn line(from: [f64; 2], to: [f64; 2]) -> Vec<InputPoint> {
+    (0..=40)
+        .map(|i| {
+            let t = i as f64 / 40.0;
+            let point = [from[0] + (to[0] - from[0]) * t, from[1] + (to[1] - from[1]) * t];
+            InputPoint::Array(point, Some(0.5))
+        })
+        .collect()
+}
+
+fn even_options() -> StrokeOptions {
+    StrokeOptions {
+        size: Some(10.0),
+        thinning: Some(0.0),
+        smoothing: Some(0.0),
+        streamline: Some(0.0),
+        last: Some(true),
+        // A round start cap, rather than the original one that turns back
+        // over the line
+        start: Some(TaperOptions {
+            cap_style: Some(CapStyle::default()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_even_line_metrics() {
+    let options = even_options();
+    let points = get_stroke_points(&line([0.0, 0.0], [100.0, 0.0]), &options);
+    let metrics = get_stroke_metrics(&points, &options).unwrap();
+
+    // A 100 by 10 rectangle with a round cap at each end
+    assert!((metrics.length - 100.0).abs() < 1e-9);
+    assert!((metrics.area - (1000.0 + PI * 25.0)).abs() < 10.0);
+    assert!((metrics.centroid[0] - 50.0).abs() < 0.1);
+    assert!(metrics.centroid[1].abs() < 0.1);
+    assert!((metrics.bounds.min[0] + 5.0).abs() < 0.1);
+    assert!((metrics.bounds.max[1] - 5.0).abs() < 0.1);
+    assert_eq!((metrics.min_radius, metrics.max_radius), (5.0, 5.0));
+    assert!((metrics.average_radius - 5.0).abs() < 1e-9);
+
+    // Turned to lie along a diagonal, the bounds grow but the oriented bounds don't
+    let end = [100.0 / 2f64.sqrt(); 2];
+    let points = get_stroke_points(&line([0.0, 0.0], end), &options);
+    let diagonal = get_stroke_metrics(&points, &options).unwrap();
+    assert!(diagonal.bounds.width() > 80.0 && diagonal.bounds.height() > 80.0);
+
+    let oriented = diagonal.oriented_bounds;
+    let [long, short] = if oriented.size[0] > oriented.size[1] {
+        oriented.size
+    } else {
+        [oriented.size[1], oriented.size[0]]
+    };
+    assert!((long - 110.0).abs() < 0.2, "{:?}", oriented);
+    assert!((short - 10.0).abs() < 0.2, "{:?}", oriented);
+    // Either side of the rectangle may be its x axis
+    let turn = (oriented.angle - FRAC_PI_4).rem_euclid(FRAC_PI_2);
+    assert!(turn.min(FRAC_PI_2 - turn) < 0.01, "{:?}", oriented);
+    assert!((diagonal.area - metrics.area).abs() < 1.0);
+
+    // The corners are around the centroid
+    let corners = oriented.corners();
+    let middle = [
+        corners.iter().map(|c| c[0]).sum::<f64>() / 4.0,
+        corners.iter().map(|c| c[1]).sum::<f64>() / 4.0,
+    ];
+    assert!((middle[0] - diagonal.centroid[0]).abs() < 0.1);
+    assert!((middle[1] - diagonal.centroid[1]).abs() < 0.1);
+
+    assert!(get_stroke_metrics(&[], &options).is_none());
+}
+
+#[test]
+fn test_tapered_and_overlapping_metrics() {
+    let options = StrokeOptions {
+        start: Some(TaperOptions {
+            taper: Some(TaperType::Number(50.0)),
+            ..Default::default()
+        }),
+        ..even_options()
+    };
+    let points = get_stroke_points(&line([0.0, 0.0], [100.0, 0.0]), &options);
+    let metrics = get_stroke_metrics(&points, &options).unwrap();
+
+    // The taper thins the first half of the line
+    assert!(metrics.min_radius < 3.0);
+    assert_eq!(metrics.max_radius, 5.0);
+    assert!(metrics.average_radius > 4.0 && metrics.average_radius < 4.8);
+    assert!(metrics.area < 1000.0 + PI * 25.0);
+    assert!(metrics.centroid[0] > 50.0);
+
+    // Going back over a line doesn't fill it twice
+    let mut there_and_back = line([0.0, 0.0], [100.0, 0.0]);
+    there_and_back.extend(line([100.0, 0.0], [0.0, 0.0]).into_iter().skip(1));
+    let options = even_options();
+    let points = get_stroke_points(&there_and_back, &options);
+    let metrics = get_stroke_metrics(&points, &options).unwrap();
+    assert!((metrics.length - 200.0).abs() < 1e-6);
+    assert!(metrics.area > 1000.0 && metrics.area < 1000.0 + PI * 25.0);
+}
@@ -10,6 +10,7 @@ fn test_get_stroke_points_with_empty_array() {
 }
 
 #[test]
+#[allow(clippy::len_zero)]
 fn test_get_stroke_points_with_one_point() {
     let points = vec![InputPoint::Array([100.0, 100.0], Some(0.5))];
     let options = StrokeOptions::default();
@@ -49,9 +50,9 @@ fn test_get_stroke_points_with_multiple_points() {
 #[test]
 fn test_get_stroke_points_with_object_input() {
     let points = vec![
-        InputPoint::Struct { x: 100.0, y: 100.0, pressure: Some(0.5) },
-        InputPoint::Struct { x: 200.0, y: 150.0, pressure: Some(0.7) },
-        InputPoint::Struct { x: 300.0, y: 100.0, pressure: Some(0.5) },
+        InputPoint::Struct { x: 100.0, y: 100.0, pressure: Some(0.5), timestamp: None, orientation: None },
+        InputPoint::Struct { x: 200.0, y: 150.0, pressure: Some(0.7), timestamp: None, orientation: None },
+        InputPoint::Struct { x: 300.0, y: 100.0, pressure: Some(0.5), timestamp: None, orientation: None },
     ];
     
     let options = StrokeOptions::default();
@@ -89,4 +90,40 @@ fn test_get_stroke_points_with_simulated_pressure() {
         assert!(point.pressure >= 0.0);
         assert!(point.pressure <= 1.0);
     }
-} 
\ No newline at end of file
+} 
+
+#[test]
+fn test_get_stroke_points_with_backfill() {
+    // Small steps at the start, before the line reaches its minimum length
+    let points: Vec<InputPoint> = (0..40)
+        .map(|i| InputPoint::Array([i as f64 * 2.0, (i as f64 * 0.3).sin() * 10.0], None))
+        .collect();
+
+    let options = StrokeOptions::default();
+    let skipped = get_stroke_points(&points, &options);
+
+    let options = StrokeOptions {
+        backfill: Some(true),
+        ..Default::default()
+    };
+    let backfilled = get_stroke_points(&points, &options);
+
+    // The leading points are kept instead of dropped
+    assert!(backfilled.len() > skipped.len());
+    assert!(backfilled[1].running_length < skipped[1].running_length);
+
+    // Each point's distance and running length follow from the point before it
+    let mut running_length = 0.0;
+    for pair in backfilled.windows(2) {
+        let distance = ((pair[1].point[0] - pair[0].point[0]).powi(2)
+            + (pair[1].point[1] - pair[0].point[1]).powi(2))
+        .sqrt();
+        running_length += distance;
+
+        assert!((pair[1].distance - distance).abs() < 1e-9);
+        assert!((pair[1].running_length - running_length).abs() < 1e-9);
+    }
+
+    // The first point takes its vector from the second
+    assert_eq!(backfilled[0].vector, backfilled[1].vector);
+}
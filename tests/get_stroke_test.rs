@@ -1,4 +1,9 @@
-use freedraw::{get_stroke, get_svg_path_from_stroke, InputPoint, StrokeOptions};
+use freedraw::{
+    get_stroke, get_stroke_outline, get_stroke_outline_points, get_stroke_points,
+    get_svg_path_from_stroke, ArcResolution, CapStyle, InputPoint, JoinStyle, Nib,
+    PenOrientation, PressureSimulation, StrokeOptions, TaperOptions,
+};
+use std::f64::consts::PI;
 use std::fs::File;
 use std::io::Read;
 use serde_json::Value;
@@ -39,6 +44,8 @@ fn convert_json_to_input_points(points_json: &[Value]) -> Vec<InputPoint> {
                     x: obj["x"].as_f64().unwrap(),
                     y: obj["y"].as_f64().unwrap(),
                     pressure: obj.get("pressure").and_then(|p| p.as_f64()),
+                    timestamp: None,
+                    orientation: None,
                 }
             }
         })
@@ -158,4 +165,260 @@ fn test_svg_path_conversion() {
     
     // Print the SVG path for demonstration
     println!("SVG Path for Number Pairs: {}", path_data);
-} 
\ No newline at end of file
+} 
+
+// A straight line along the x axis at a steady speed, sampled at the given rate
+fn sampled_line(rate: f64) -> Vec<InputPoint> {
+    let speed = 0.5; // pixels per millisecond
+    let interval = 1000.0 / rate;
+    (0..(1000.0 / interval) as usize)
+        .map(|i| {
+            let timestamp = i as f64 * interval;
+            InputPoint::new(timestamp * speed, 0.0, None).with_timestamp(timestamp)
+        })
+        .collect()
+}
+
+// The half-width of the outline around the middle of the line
+fn half_width(outline: &[[f64; 2]]) -> f64 {
+    outline
+        .iter()
+        .filter(|p| p[0] > 200.0 && p[0] < 300.0)
+        .fold(0.0, |acc, p| f64::max(acc, p[1].abs()))
+}
+
+#[test]
+fn test_velocity_pressure_simulation_ignores_sample_rate() {
+    let mut options = StrokeOptions {
+        streamline: Some(0.0),
+        last: Some(true),
+        ..Default::default()
+    };
+
+    let pen = half_width(&get_stroke(&sampled_line(240.0), &options));
+    let mouse = half_width(&get_stroke(&sampled_line(60.0), &options));
+
+    // Measured by distance, the pen's shorter steps read as a slower, thicker line
+    assert!(pen - mouse > 1.0);
+
+    options.pressure_simulation = Some(PressureSimulation::Velocity);
+
+    let pen = half_width(&get_stroke(&sampled_line(240.0), &options));
+    let mouse = half_width(&get_stroke(&sampled_line(60.0), &options));
+
+    assert!((pen - mouse).abs() < 0.1);
+}
+
+// The width of an outline across the given axis
+fn extent(outline: &[[f64; 2]], axis: usize) -> f64 {
+    let min = outline.iter().fold(f64::MAX, |acc, p| acc.min(p[axis]));
+    let max = outline.iter().fold(f64::MIN, |acc, p| acc.max(p[axis]));
+    max - min
+}
+
+#[test]
+fn test_fixed_nib_depends_on_direction() {
+    let options = StrokeOptions {
+        size: Some(20.0),
+        thinning: Some(0.0),
+        nib: Some(Nib::Fixed {
+            angle: 0.0,
+            aspect: 0.1,
+        }),
+        last: Some(true),
+        ..Default::default()
+    };
+
+    let line = |to: [f64; 2]| -> Vec<InputPoint> {
+        (0..=20)
+            .map(|i| InputPoint::Array([to[0] * i as f64 / 20.0, to[1] * i as f64 / 20.0], None))
+            .collect()
+    };
+
+    // Moving along the nib's edge gives a hairline, moving across it gives the full width
+    let horizontal = get_stroke(&line([200.0, 0.0]), &options);
+    let vertical = get_stroke(&line([0.0, 200.0]), &options);
+
+    assert!(extent(&horizontal, 1) < 4.0);
+    assert!(extent(&vertical, 0) > 19.0);
+
+    // A round tip is as thick in both directions
+    let round = StrokeOptions { nib: None, ..options };
+    let horizontal = get_stroke(&line([200.0, 0.0]), &round);
+    let vertical = get_stroke(&line([0.0, 200.0]), &round);
+
+    assert!((extent(&horizontal, 1) - extent(&vertical, 0)).abs() < 0.01);
+}
+
+#[test]
+fn test_tilt_nib_follows_pen_orientation() {
+    let options = StrokeOptions {
+        size: Some(20.0),
+        thinning: Some(0.0),
+        nib: Some(Nib::Tilt { aspect: 0.2 }),
+        last: Some(true),
+        ..Default::default()
+    };
+
+    // A horizontal line drawn with the pen leaning along the line or across it
+    let line = |tilt_x: f64, tilt_y: f64| -> Vec<InputPoint> {
+        (0..=20)
+            .map(|i| InputPoint::Struct {
+                x: i as f64 * 10.0,
+                y: 0.0,
+                pressure: None,
+                timestamp: None,
+                orientation: Some(PenOrientation {
+                    tilt_x: Some(tilt_x),
+                    tilt_y: Some(tilt_y),
+                    ..Default::default()
+                }),
+            })
+            .collect()
+    };
+
+    let along = get_stroke(&line(60.0, 0.0), &options);
+    let across = get_stroke(&line(0.0, 60.0), &options);
+
+    assert!(extent(&along, 1) < extent(&across, 1) / 2.0);
+}
+
+#[test]
+fn test_join_styles_at_sharp_corners() {
+    // Right along the x axis, then sharply back up and to the left
+    let mut points: Vec<InputPoint> = (0..=20)
+        .map(|i| InputPoint::Array([i as f64 * 5.0, 0.0], None))
+        .collect();
+    points.extend(
+        (1..=20).map(|i| InputPoint::Array([100.0 - i as f64 * 5.0, i as f64 * 5.0], None)),
+    );
+
+    let reach = |join: JoinStyle| {
+        let options = StrokeOptions {
+            size: Some(16.0),
+            thinning: Some(0.0),
+            streamline: Some(0.0),
+            join: Some(join),
+            last: Some(true),
+            ..Default::default()
+        };
+        let outline = get_stroke(&points, &options);
+        outline.iter().fold(f64::MIN, |acc, p| acc.max(p[0]))
+    };
+
+    let round = reach(JoinStyle::Round);
+    let bevel = reach(JoinStyle::Bevel);
+    let miter = reach(JoinStyle::Miter { limit: 4.0 });
+
+    // A round join reaches one radius past the corner
+    assert!((round - 108.0).abs() < 0.5);
+    assert!(bevel < round - 1.0);
+    assert!(miter > round + 1.0);
+
+    // Past the limit, a miter falls back to a bevel
+    assert_eq!(reach(JoinStyle::Miter { limit: 1.0 }), bevel);
+}
+
+#[test]
+fn test_cap_styles() {
+    let points: Vec<InputPoint> = (0..=20)
+        .map(|i| InputPoint::Array([i as f64 * 10.0, 0.0], None))
+        .collect();
+
+    let outline = |cap_style: CapStyle| {
+        let cap = Some(TaperOptions {
+            cap_style: Some(cap_style),
+            ..Default::default()
+        });
+        let options = StrokeOptions {
+            size: Some(16.0),
+            thinning: Some(0.0),
+            streamline: Some(0.0),
+            start: cap.clone(),
+            end: cap,
+            last: Some(true),
+            ..Default::default()
+        };
+        get_stroke(&points, &options)
+    };
+    let reach = |outline: &[[f64; 2]]| {
+        let start = outline.iter().fold(f64::MAX, |acc, p| acc.min(p[0]));
+        let end = outline.iter().fold(f64::MIN, |acc, p| acc.max(p[0]));
+        (start, end)
+    };
+
+    // Round and square caps reach one radius past the ends, butt caps stop at them
+    let (start, end) = reach(&outline(CapStyle::default()));
+    assert!((start + 8.0).abs() < 0.01 && (end - 208.0).abs() < 0.01);
+
+    let (start, end) = reach(&outline(CapStyle::Square));
+    assert!((start + 8.0).abs() < 1e-9 && (end - 208.0).abs() < 1e-9);
+
+    let (start, end) = reach(&outline(CapStyle::Butt));
+    assert!(start.abs() < 1e-9 && (end - 200.0).abs() < 1e-9);
+
+    // Without a style, the start cap is the original round one, which turns
+    // back over the line instead of reaching past its start
+    let options = StrokeOptions {
+        size: Some(16.0),
+        thinning: Some(0.0),
+        streamline: Some(0.0),
+        last: Some(true),
+        ..Default::default()
+    };
+    let (start, end) = reach(&get_stroke(&points, &options));
+    assert!(start.abs() < 0.01 && (end - 208.0).abs() < 0.01);
+
+    // A finer resolution adds points to both caps
+    let coarse = outline(CapStyle::Round(ArcResolution::Segments(4)));
+    let fine = outline(CapStyle::Round(ArcResolution::Segments(16)));
+    assert_eq!(fine.len(), coarse.len() + 24);
+
+    // A tolerance gives larger strokes more segments
+    let resolution = ArcResolution::Tolerance(0.1);
+    assert!(resolution.segments(100.0, PI) > resolution.segments(10.0, PI));
+    assert_eq!(ArcResolution::Segments(4).segments(100.0, PI), 4);
+}
+
+#[test]
+fn test_stroke_outline_parts() {
+    let points: Vec<InputPoint> = (0..=40)
+        .map(|i| {
+            let angle = i as f64 / 40.0 * PI;
+            InputPoint::Array([100.0 * angle.cos(), 100.0 * angle.sin()], None)
+        })
+        .collect();
+    let options = StrokeOptions {
+        size: Some(16.0),
+        thinning: Some(0.0),
+        ..Default::default()
+    };
+
+    let stroke_points = get_stroke_points(&points, &options);
+    let outline = get_stroke_outline(&stroke_points, &options);
+
+    assert_eq!(outline.left.len(), outline.left_indices.len());
+    assert_eq!(outline.right.len(), outline.right_indices.len());
+    assert!(!outline.start_cap.is_empty() && !outline.end_cap.is_empty());
+
+    // Each side point is offset from the stroke point it came from
+    for (side, indices) in [
+        (&outline.left, &outline.left_indices),
+        (&outline.right, &outline.right_indices),
+    ] {
+        assert!(indices.windows(2).all(|w| w[0] <= w[1]));
+        for (point, &index) in side.iter().zip(indices.iter()) {
+            let source = stroke_points[index].point;
+            let distance = (point[0] - source[0]).hypot(point[1] - source[1]);
+            assert!((distance - 8.0).abs() < 1e-9);
+        }
+    }
+
+    // Joined together, the parts make the usual outline
+    let mut polygon = outline.start_cap.clone();
+    polygon.extend(&outline.right);
+    polygon.extend(&outline.end_cap);
+    polygon.extend(outline.left.iter().rev());
+    polygon.push(polygon[0]);
+    assert_eq!(polygon, get_stroke_outline_points(&stroke_points, &options));
+}
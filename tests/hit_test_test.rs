@@ -0,0 +1,113 @@
+use freedraw::{
+    contains_point, distance_to_stroke, get_stroke_outline_points, get_stroke_points, CapStyle,
+    InputPoint, JoinStyle, StrokeOptions, StrokeShape, TaperOptions,
+};
+use std::fs::File;
+use std::io::Read;
+
+fn load_sample_points() -> Vec<InputPoint> {
+    let mut file = File::open("tests/sample.json").expect("Could not open sample.json");
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).expect("Could not read file");
+    let points: Vec<Vec<f64>> = serde_json::from_str(&contents).expect("Could not parse JSON");
+
+    points
+        .iter()
+        .map(|p| InputPoint::Array([p[0], p[1]], p.get(2).copied()))
+        .collect()
+}
+
+#[test]
+fn test_hit_straight_line() {
+    let points: Vec<InputPoint> = (0..=20)
+        .map(|i| InputPoint::Array([i as f64 * 10.0, 50.0], Some(0.5)))
+        .collect();
+    let options = StrokeOptions {
+        size: Some(16.0),
+        thinning: Some(0.0),
+        last: Some(true),
+        ..Default::default()
+    };
+    let stroke_points = get_stroke_points(&points, &options);
+    let outline = get_stroke_outline_points(&stroke_points, &options);
+
+    let shapes = [
+        StrokeShape::Outline {
+            points: &stroke_points,
+            outline: &outline,
+        },
+        StrokeShape::Centerline {
+            points: &stroke_points,
+            options: &options,
+        },
+    ];
+
+    for shape in &shapes {
+        // Inside the stroke, which is 8 wide on each side of the line
+        let hit = contains_point([100.0, 53.0], shape, 0.0).unwrap();
+        assert!((hit.distance + 5.0).abs() < 0.5, "{:?}", hit);
+        assert!((stroke_points[hit.index].point[0] - 100.0).abs() < 10.0);
+        let expected_length = stroke_points[hit.index].running_length;
+        assert!((hit.running_length - expected_length).abs() < 10.0);
+
+        // Just outside, but within the tolerance
+        assert!(contains_point([100.0, 61.0], shape, 0.0).is_none());
+        let hit = contains_point([100.0, 61.0], shape, 4.0).unwrap();
+        assert!((hit.distance - 3.0).abs() < 0.5, "{:?}", hit);
+
+        // Far away
+        let hit = distance_to_stroke([100.0, 150.0], shape).unwrap();
+        assert!((hit.distance - 92.0).abs() < 0.5, "{:?}", hit);
+    }
+
+    // Nothing to hit
+    let empty = StrokeShape::Centerline {
+        points: &[],
+        options: &options,
+    };
+    assert!(distance_to_stroke([0.0, 0.0], &empty).is_none());
+}
+
+#[test]
+fn test_hit_outline_and_centerline_agree() {
+    // Round corners and a round start cap, like the circles along the
+    // centerline, rather than the original start cap that cuts into it
+    let options = StrokeOptions {
+        join: Some(JoinStyle::Round),
+        start: Some(TaperOptions {
+            cap_style: Some(CapStyle::default()),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let stroke_points = get_stroke_points(&load_sample_points(), &options);
+    let outline = get_stroke_outline_points(&stroke_points, &options);
+
+    let outline_shape = StrokeShape::Outline {
+        points: &stroke_points,
+        outline: &outline,
+    };
+    let centerline_shape = StrokeShape::Centerline {
+        points: &stroke_points,
+        options: &options,
+    };
+
+    let mut inside = 0;
+    for x in (0..300).step_by(3) {
+        for y in (0..300).step_by(3) {
+            let point = [x as f64, y as f64];
+            let a = distance_to_stroke(point, &outline_shape).unwrap();
+            let b = distance_to_stroke(point, &centerline_shape).unwrap();
+
+            // Away from the edge, both shapes agree on which side the point
+            // is. The outline cuts a little inside tight curves.
+            if b.distance.abs() > 4.0 {
+                assert_eq!(a.distance < 0.0, b.distance < 0.0, "{:?} {:?} {:?}", point, a, b);
+            }
+            if a.distance < 0.0 {
+                inside += 1;
+            }
+        }
+    }
+    assert!(inside > 100);
+}
@@ -0,0 +1,130 @@
+use freedraw::{
+    get_stroke, predict_points, InputPoint, PredictionModel, PredictionOptions, Resampling,
+    SmootherType, StrokeBuilder, StrokeOptions,
+};
+
+/// Points along a path, sampled every 8 milliseconds.
+fn sampled(count: usize, path: impl Fn(f64) -> [f64; 2]) -> Vec<InputPoint> {
+    (0..count)
+        .map(|i| {
+            let t = i as f64 * 8.0;
+            let [x, y] = path(t);
+            InputPoint::Struct {
+                x,
+                y,
+                pressure: Some(0.4),
+                timestamp: Some(t),
+                orientation: None,
+            }
+        })
+        .collect()
+}
+
+fn options(model: PredictionModel) -> PredictionOptions {
+    PredictionOptions {
+        model: Some(model),
+        latency: Some(24.0),
+        count: Some(3),
+    }
+}
+
+fn assert_near(a: [f64; 2], b: [f64; 2]) {
+    assert!((a[0] - b[0]).abs() < 1e-6 && (a[1] - b[1]).abs() < 1e-6, "{:?} {:?}", a, b);
+}
+
+#[test]
+fn test_constant_velocity() {
+    let points = sampled(10, |t| [t * 0.5, 10.0 + t * 0.25]);
+    let predicted = predict_points(&points, &options(PredictionModel::ConstantVelocity));
+
+    // The points carry on along the line, 8 milliseconds apart
+    assert_eq!(predicted.len(), 3);
+    for (i, point) in predicted.iter().enumerate() {
+        let t = 72.0 + 8.0 * (i + 1) as f64;
+        assert_near(point.point(), [t * 0.5, 10.0 + t * 0.25]);
+        assert_eq!(point.timestamp(), Some(t));
+        assert_eq!(point.pressure(), Some(0.4));
+    }
+
+    // Without timestamps, the points are taken to be a frame apart
+    let frames: Vec<InputPoint> = (0..5)
+        .map(|i| InputPoint::Array([i as f64 * 3.0, 0.0], None))
+        .collect();
+    let predicted = predict_points(
+        &frames,
+        &PredictionOptions {
+            latency: Some(1000.0 / 60.0),
+            count: Some(1),
+            ..Default::default()
+        },
+    );
+    assert!(matches!(predicted[..], [InputPoint::Array(point, None)] if point == [15.0, 0.0]));
+
+    // A single point has nowhere to go
+    assert!(predict_points(&frames[..1], &PredictionOptions::default()).is_empty());
+}
+
+#[test]
+fn test_acceleration_and_curve_fit() {
+    // The pen speeds up along x and turns along y
+    let path = |t: f64| [t * 0.5 + t * t * 0.01, t * t * 0.002];
+    let points = sampled(10, path);
+    let expected = path(72.0 + 24.0);
+
+    for model in [
+        PredictionModel::ConstantAcceleration,
+        PredictionModel::CurveFit { history: 6 },
+    ] {
+        let predicted = predict_points(&points, &options(model));
+        assert_near(predicted[2].point(), expected);
+    }
+
+    // Going on at a constant speed falls behind
+    let predicted = predict_points(&points, &options(PredictionModel::ConstantVelocity));
+    assert!(predicted[2].point()[0] < expected[0] - 1.0);
+}
+
+#[test]
+fn test_builder_throws_predicted_points_away() {
+    let points = sampled(40, |t| [t * 0.8, 20.0 * (t / 80.0).sin()]);
+    let prediction = options(PredictionModel::CurveFit { history: 4 });
+
+    let stroke_options = [
+        StrokeOptions::default(),
+        StrokeOptions {
+            smoother: Some(SmootherType::OneEuro {
+                min_cutoff: 1.0,
+                beta: 0.01,
+            }),
+            ..Default::default()
+        },
+        StrokeOptions {
+            resampling: Some(Resampling::Uniform { spacing: 3.0 }),
+            ..Default::default()
+        },
+    ];
+
+    for options in stroke_options {
+        let mut builder = StrokeBuilder::new(options.clone());
+
+        for i in 0..points.len() {
+            builder.push(points[i].clone());
+            assert_eq!(builder.outline(), get_stroke(&points[..=i], &options));
+
+            // The predicted points are drawn on the end of the stroke...
+            let predicted = predict_points(&points[..=i], &prediction);
+            builder.predict(&predicted);
+            let with_predicted = [&points[..=i], &predicted[..]].concat();
+            assert_eq!(builder.outline(), get_stroke(&with_predicted, &options));
+        }
+
+        // ...and leave no trace once the real points arrive
+        builder.finish();
+        let last = StrokeOptions {
+            last: Some(true),
+            ..options
+        };
+        assert_eq!(builder.outline(), get_stroke(&points, &last));
+        assert_eq!(builder.input_points().len(), points.len());
+    }
+}
@@ -0,0 +1,151 @@
+use freedraw::{
+    get_closed_stroke, get_stroke, get_stroke_points, resample_points, InputPoint, Resampling,
+    StrokeBuilder, StrokeOptions,
+};
+
+fn distances(points: &[InputPoint]) -> Vec<f64> {
+    points
+        .windows(2)
+        .map(|pair| {
+            let [a, b] = [pair[0].point(), pair[1].point()];
+            (b[0] - a[0]).hypot(b[1] - a[1])
+        })
+        .collect()
+}
+
+/// An L shape, sampled unevenly, with the pressure rising and the time
+/// running along it.
+fn corner() -> Vec<InputPoint> {
+    [[0.0, 0.0], [3.0, 0.0], [25.0, 0.0], [50.0, 0.0], [50.0, 7.0], [50.0, 50.0]]
+        .iter()
+        .enumerate()
+        .map(|(i, &[x, y])| InputPoint::Struct {
+            x,
+            y,
+            pressure: Some(0.2 + 0.1 * i as f64),
+            timestamp: Some(x + y),
+            orientation: None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_uniform_spacing() {
+    let resampled = resample_points(&corner(), &Resampling::Uniform { spacing: 4.0 });
+
+    // The first and last points are kept
+    assert_eq!(resampled[0].point(), [0.0, 0.0]);
+    assert_eq!(resampled.last().unwrap().point(), [50.0, 50.0]);
+
+    // The points are 4 apart along the line, which is 100 long.
+    // Across the corner they're closer, as the line turns between them
+    let distances = distances(&resampled);
+    let (last, rest) = distances.split_last().unwrap();
+    assert!(*last <= 4.0 + 1e-9);
+    for (i, &distance) in rest.iter().enumerate() {
+        let point = resampled[i + 1].point();
+        if point[0] == 50.0 && point[1] > 0.0 && point[1] < 4.0 {
+            assert!(distance < 4.0);
+        } else {
+            assert!((distance - 4.0).abs() < 1e-9, "{}: {}", i, distance);
+        }
+    }
+    assert_eq!(resampled.len(), 26);
+}
+
+#[test]
+fn test_pressure_and_timestamp_are_interpolated() {
+    let resampled = resample_points(&corner(), &Resampling::Uniform { spacing: 4.0 });
+
+    // The point at (12, 0) is between (3, 0) at 0.3 and (25, 0) at 0.4
+    let point = &resampled[3];
+    assert_eq!(point.point(), [12.0, 0.0]);
+    assert!((point.pressure().unwrap() - (0.3 + 0.1 * 9.0 / 22.0)).abs() < 1e-9);
+    assert!((point.timestamp().unwrap() - 12.0).abs() < 1e-9);
+
+    // Points without pressure stay without it
+    let bare: Vec<InputPoint> = corner()
+        .iter()
+        .map(|point| InputPoint::Array(point.point(), None))
+        .collect();
+    let resampled = resample_points(&bare, &Resampling::Uniform { spacing: 4.0 });
+    assert!(resampled.iter().all(|point| point.pressure().is_none()));
+}
+
+#[test]
+fn test_adaptive_spacing_follows_curvature() {
+    // A straight line, then a tight circle
+    let mut points: Vec<InputPoint> = (0..=10)
+        .map(|i| InputPoint::Array([i as f64 * 10.0, 0.0], None))
+        .collect();
+    for i in 1..=16 {
+        let angle = i as f64 / 16.0 * std::f64::consts::TAU;
+        let point = [100.0 + 10.0 * angle.sin(), 10.0 - 10.0 * angle.cos()];
+        points.push(InputPoint::Array(point, None));
+    }
+
+    let resampling = Resampling::Adaptive {
+        tolerance: 0.05,
+        max_spacing: 8.0,
+    };
+    let resampled = resample_points(&points, &resampling);
+    let distances = distances(&resampled);
+
+    let straight: Vec<f64> = resampled
+        .windows(2)
+        .zip(&distances)
+        .filter(|(pair, _)| pair[1].point()[0] < 85.0)
+        .map(|(_, &distance)| distance)
+        .collect();
+    let curved: Vec<f64> = resampled
+        .windows(2)
+        .zip(&distances)
+        .filter(|(pair, _)| pair[0].point()[1] > 2.0)
+        .map(|(_, &distance)| distance)
+        .collect();
+
+    assert!(straight.iter().all(|&distance| (distance - 8.0).abs() < 1e-9));
+    assert!(curved.iter().all(|&distance| distance < 3.0));
+}
+
+#[test]
+fn test_resampling_option() {
+    let resampling = Resampling::Uniform { spacing: 2.0 };
+    let options = StrokeOptions {
+        size: Some(8.0),
+        resampling: Some(resampling),
+        ..Default::default()
+    };
+
+    // The option resamples the points before the stroke points are made
+    let points = corner();
+    let plain = StrokeOptions {
+        resampling: None,
+        ..options.clone()
+    };
+    let positions = |points: &[InputPoint], options: &StrokeOptions| -> Vec<[f64; 2]> {
+        get_stroke_points(points, options).iter().map(|p| p.point).collect()
+    };
+    assert_eq!(
+        positions(&points, &options),
+        positions(&resample_points(&points, &resampling), &plain)
+    );
+
+    // Closed loops are resampled all the way around
+    let ring = get_closed_stroke(&points, &options);
+    assert!(!ring.inner.is_empty());
+    assert!(ring.outer.len() > get_closed_stroke(&points, &plain).outer.len());
+
+    // Building the stroke point by point gives the same outline
+    let mut builder = StrokeBuilder::new(options.clone());
+    for point in &points {
+        builder.push(point.clone());
+        assert_eq!(builder.outline(), get_stroke(builder.input_points(), &options));
+    }
+    builder.finish();
+    let last = StrokeOptions {
+        last: Some(true),
+        ..options
+    };
+    assert_eq!(builder.outline(), get_stroke(&points, &last));
+}
@@ -0,0 +1,138 @@
+use freedraw::{
+    get_stroke_outline_points, get_stroke_points, select_with_lasso, Containment, InputPoint,
+    StrokeOptions, StrokePoint, StrokeShape,
+};
+
+/// A straight line from one point to another.
+fn line(from: [f64; 2], to: [f64; 2], options: &StrokeOptions) -> Vec<StrokePoint> {
+    let points: Vec<InputPoint> = (0..=20)
+        .map(|i| {
+            let t = i as f64 / 20.0;
+            let point = [from[0] + (to[0] - from[0]) * t, from[1] + (to[1] - from[1]) * t];
+            InputPoint::Array(point, Some(0.5))
+        })
+        .collect();
+
+    get_stroke_points(&points, options)
+}
+
+#[test]
+fn test_select_with_lasso() {
+    let options = StrokeOptions {
+        size: Some(8.0),
+        last: Some(true),
+        ..Default::default()
+    };
+
+    // A square lasso from 0 to 100, drawn by hand without closing it
+    let lasso: Vec<[f64; 2]> = (0..40)
+        .map(|i| match i / 10 {
+            0 => [i as f64 * 10.0, 0.0],
+            1 => [100.0, (i - 10) as f64 * 10.0],
+            2 => [100.0 - (i - 20) as f64 * 10.0, 100.0],
+            _ => [0.0, 100.0 - (i - 30) as f64 * 10.0],
+        })
+        .collect();
+
+    let strokes = [
+        line([20.0, 20.0], [80.0, 20.0], &options),
+        // 60 of its 100 units are inside
+        line([40.0, 50.0], [140.0, 50.0], &options),
+        line([150.0, 20.0], [180.0, 80.0], &options),
+    ];
+    let outlines: Vec<Vec<[f64; 2]>> = strokes
+        .iter()
+        .map(|points| get_stroke_outline_points(points, &options))
+        .collect();
+
+    let centerlines: Vec<StrokeShape> = strokes
+        .iter()
+        .map(|points| StrokeShape::Centerline {
+            points,
+            options: &options,
+        })
+        .collect();
+    let outline_shapes: Vec<StrokeShape> = strokes
+        .iter()
+        .zip(&outlines)
+        .map(|(points, outline)| StrokeShape::Outline { points, outline })
+        .collect();
+
+    for shapes in [&centerlines, &outline_shapes] {
+        let selection = select_with_lasso(&lasso, shapes, 1.0);
+        let containment: Vec<Containment> = selection.iter().map(|s| s.containment).collect();
+        assert_eq!(
+            containment,
+            vec![Containment::Inside, Containment::Partial, Containment::Outside]
+        );
+        assert_eq!(selection[0].coverage, 1.0);
+        assert_eq!(selection[2].coverage, 0.0);
+    }
+
+    // Along the centerline, the coverage is the share of the line inside
+    let selection = select_with_lasso(&lasso, &centerlines, 1.0);
+    assert!((selection[1].coverage - 0.6).abs() < 1e-9);
+
+    // A lower threshold counts most of a stroke as inside
+    let selection = select_with_lasso(&lasso, &centerlines, 0.5);
+    assert_eq!(selection[1].containment, Containment::Inside);
+
+    // A lasso with too few points selects nothing
+    let selection = select_with_lasso(&lasso[..2], &centerlines, 1.0);
+    assert!(selection.iter().all(|s| s.containment == Containment::Outside));
+}
+
+#[test]
+fn test_select_with_crossed_lasso() {
+    let options = StrokeOptions::default();
+
+    // A figure eight, where the loops wind in opposite directions
+    let lasso = [[0.0, 0.0], [100.0, 100.0], [100.0, 0.0], [0.0, 100.0]];
+    let strokes = [
+        line([10.0, 40.0], [10.0, 60.0], &options),
+        line([45.0, 5.0], [55.0, 5.0], &options),
+    ];
+    let shapes: Vec<StrokeShape> = strokes
+        .iter()
+        .map(|points| StrokeShape::Centerline {
+            points,
+            options: &options,
+        })
+        .collect();
+
+    let selection = select_with_lasso(&lasso, &shapes, 1.0);
+    assert_eq!(selection[0].containment, Containment::Inside);
+    assert_eq!(selection[1].containment, Containment::Outside);
+}
+
+#[test]
+fn test_select_with_lasso_inside_stroke() {
+    let options = StrokeOptions {
+        size: Some(60.0),
+        thinning: Some(0.0),
+        last: Some(true),
+        ..Default::default()
+    };
+    let points = line([0.0, 50.0], [200.0, 50.0], &options);
+    let outline = get_stroke_outline_points(&points, &options);
+
+    // A small lasso inside the stroke, away from its centerline
+    let lasso = [[90.0, 60.0], [110.0, 60.0], [110.0, 70.0], [90.0, 70.0]];
+
+    let shapes = [
+        StrokeShape::Outline {
+            points: &points,
+            outline: &outline,
+        },
+        StrokeShape::Centerline {
+            points: &points,
+            options: &options,
+        },
+    ];
+    let selection = select_with_lasso(&lasso, &shapes, 1.0);
+
+    // The outline takes in the lasso, but the centerline misses it
+    assert_eq!(selection[0].containment, Containment::Partial);
+    assert_eq!(selection[0].coverage, 0.0);
+    assert_eq!(selection[1].containment, Containment::Outside);
+}
@@ -0,0 +1,161 @@
+use freedraw::{
+    distance_to_stroke, get_stroke, get_stroke_points, simplify_outline, simplify_points,
+    InputPoint, Resampling, Simplification, StrokeOptions, StrokeShape,
+};
+use serde_json::Value;
+use std::fs::File;
+use std::io::Read;
+
+fn load_test_data() -> serde_json::Value {
+    let mut file = File::open("tests/inputs.json").expect("Could not open input.json");
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).expect("Could not read file");
+    serde_json::from_str(&contents).expect("Could not parse JSON")
+}
+
+fn convert_json_to_input_points(points_json: &[Value]) -> Vec<InputPoint> {
+    points_json
+        .iter()
+        .map(|point| {
+            let coords = point.as_array().unwrap();
+            InputPoint::Array(
+                [coords[0].as_f64().unwrap(), coords[1].as_f64().unwrap()],
+                coords.get(2).and_then(|p| p.as_f64()),
+            )
+        })
+        .collect()
+}
+
+fn hey() -> Vec<InputPoint> {
+    let data = load_test_data();
+    convert_json_to_input_points(data["hey"].as_array().unwrap())
+}
+
+fn methods(tolerance: f64) -> [Simplification; 2] {
+    [
+        Simplification::RamerDouglasPeucker { tolerance },
+        Simplification::VisvalingamWhyatt { tolerance },
+    ]
+}
+
+/// How far a point is from the nearest segment of a line.
+fn distance_to_line(point: [f64; 2], line: &[[f64; 2]]) -> f64 {
+    line.windows(2)
+        .map(|pair| {
+            let (a, b) = (pair[0], pair[1]);
+            let ab = [b[0] - a[0], b[1] - a[1]];
+            let ap = [point[0] - a[0], point[1] - a[1]];
+            let length2 = ab[0] * ab[0] + ab[1] * ab[1];
+            let t = if length2 == 0.0 {
+                0.0
+            } else {
+                ((ap[0] * ab[0] + ap[1] * ab[1]) / length2).clamp(0.0, 1.0)
+            };
+            (ap[0] - ab[0] * t).hypot(ap[1] - ab[1] * t)
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+#[test]
+fn test_simplify_points_within_tolerance() {
+    let points = hey();
+    let options = StrokeOptions::default();
+
+    for simplification in methods(0.5) {
+        let simplified = simplify_points(&points, &options, &simplification);
+        assert!(simplified.len() < points.len() / 2, "{:?}", simplification);
+
+        // The first and last points are kept, and the kept points are unchanged
+        assert_eq!(simplified[0].point(), points[0].point());
+        assert_eq!(simplified.last().unwrap().point(), points.last().unwrap().point());
+
+        let line: Vec<[f64; 2]> = simplified.iter().map(|p| p.point()).collect();
+        for point in &points {
+            assert!(distance_to_line(point.point(), &line) <= 0.5 + 1e-9);
+        }
+    }
+
+    // A tolerance of less than 0 keeps every point
+    let simplification = Simplification::RamerDouglasPeucker { tolerance: -1.0 };
+    assert_eq!(simplify_points(&points, &options, &simplification).len(), points.len());
+}
+
+#[test]
+fn test_simplify_points_keeps_pressure() {
+    // A straight line whose pressure rises and then stays the same
+    let points: Vec<InputPoint> = (0..=20)
+        .map(|i| InputPoint::Array([i as f64 * 5.0, 0.0], Some((i as f64 / 10.0).min(1.0))))
+        .collect();
+    let simplification = Simplification::RamerDouglasPeucker { tolerance: 0.1 };
+
+    // When the width follows the pressure, points are kept where it changes
+    let options = StrokeOptions {
+        size: Some(10.0),
+        simulate_pressure: Some(false),
+        ..Default::default()
+    };
+    let simplified = simplify_points(&points, &options, &simplification);
+    let kept: Vec<[f64; 2]> = simplified.iter().map(|p| p.point()).collect();
+    assert_eq!(kept, vec![[0.0, 0.0], [50.0, 0.0], [100.0, 0.0]]);
+    assert_eq!(simplified[1].pressure(), Some(1.0));
+
+    // When it doesn't, only the ends are needed
+    let options = StrokeOptions {
+        simulate_pressure: Some(true),
+        ..options
+    };
+    assert_eq!(simplify_points(&points, &options, &simplification).len(), 2);
+}
+
+#[test]
+fn test_simplified_stroke_looks_the_same() {
+    let points = hey();
+    let options = StrokeOptions {
+        size: Some(8.0),
+        simulate_pressure: Some(false),
+        resampling: Some(Resampling::Uniform { spacing: 2.0 }),
+        ..Default::default()
+    };
+    let outline = get_stroke(&points, &options);
+
+    for simplification in methods(0.5) {
+        let simplified = simplify_points(&points, &options, &simplification);
+        let stroke_points = get_stroke_points(&simplified, &options);
+        let shape = StrokeShape::Centerline {
+            points: &stroke_points,
+            options: &options,
+        };
+
+        // No part of the original stroke is further than the tolerance
+        // outside the simplified one
+        for &point in &outline {
+            let hit = distance_to_stroke(point, &shape).unwrap();
+            assert!(hit.distance <= 0.5, "{:?}: {:?}", simplification, hit);
+        }
+    }
+}
+
+#[test]
+fn test_simplify_outline() {
+    let options = StrokeOptions {
+        size: Some(8.0),
+        ..Default::default()
+    };
+    let outline = get_stroke(&hey(), &options);
+
+    for simplification in methods(0.25) {
+        // The outline ends with its first point, and so does the simplified one
+        let simplified = simplify_outline(&outline, &simplification);
+        assert!(simplified.len() < outline.len() * 3 / 4, "{:?}", simplification);
+        assert_eq!(simplified[0], outline[0]);
+        assert_eq!(simplified.last(), simplified.first());
+
+        for &point in &outline {
+            assert!(distance_to_line(point, &simplified) <= 0.25 + 1e-9);
+        }
+
+        // Without the repeated point, the outline is still taken to be closed
+        let open = simplify_outline(&outline[..outline.len() - 1], &simplification);
+        assert_eq!(open, simplified[..simplified.len() - 1]);
+    }
+}
@@ -0,0 +1,154 @@
+use freedraw::{
+    get_stroke, get_stroke_points, InputPoint, Smoother, SmootherType, StrokeBuilder,
+    StrokeOptions,
+};
+
+/// A line along y = 0 at `speed` units per millisecond, sampled every 8
+/// milliseconds, that shakes up and down by `jitter`.
+fn shaky_line(count: usize, speed: f64, jitter: f64) -> Vec<InputPoint> {
+    (0..count)
+        .map(|i| {
+            let t = i as f64 * 8.0;
+            // A shake that doesn't repeat too neatly
+            let shake = jitter * ((i * 7919) % 13) as f64 / 6.0 - jitter;
+            InputPoint::Struct {
+                x: t * speed,
+                y: shake,
+                pressure: Some(0.5),
+                timestamp: Some(t),
+                orientation: None,
+            }
+        })
+        .collect()
+}
+
+fn options(smoother: SmootherType) -> StrokeOptions {
+    StrokeOptions {
+        size: Some(4.0),
+        streamline: Some(0.0),
+        smoother: Some(smoother),
+        ..Default::default()
+    }
+}
+
+/// How far the stroke points stray from y = 0 on average.
+fn shake(points: &[InputPoint], options: &StrokeOptions) -> f64 {
+    let stroke_points = get_stroke_points(points, options);
+    stroke_points.iter().map(|p| p.point[1].abs()).sum::<f64>() / stroke_points.len() as f64
+}
+
+/// How far the end of the stroke is behind the pen.
+fn lag(points: &[InputPoint], options: &StrokeOptions) -> f64 {
+    let stroke_points = get_stroke_points(points, options);
+    points.last().unwrap().point()[0] - stroke_points.last().unwrap().point[0]
+}
+
+#[test]
+fn test_smoothers_reduce_shaking() {
+    let slow = shaky_line(120, 0.05, 1.0);
+    let raw = shake(&slow, &options(SmootherType::Streamline));
+
+    let smoothers = [
+        SmootherType::Exponential {
+            time_constant: 50.0,
+        },
+        SmootherType::OneEuro {
+            min_cutoff: 1.0,
+            beta: 0.01,
+        },
+        SmootherType::Kalman {
+            process_noise: 0.01,
+            measurement_noise: 1.0,
+        },
+        SmootherType::LazyBrush { radius: 2.0 },
+    ];
+    for smoother in smoothers {
+        let smoothed = shake(&slow, &options(smoother));
+        assert!(smoothed < raw * 0.5, "{:?}: {} against {}", smoother, smoothed, raw);
+    }
+}
+
+#[test]
+fn test_one_euro_keeps_up_with_fast_moves() {
+    let one_euro = options(SmootherType::OneEuro {
+        min_cutoff: 1.0,
+        beta: 0.01,
+    });
+    let exponential = options(SmootherType::Exponential {
+        time_constant: 50.0,
+    });
+
+    // Both smooth slow movements about as much...
+    let slow = shaky_line(120, 0.05, 1.0);
+    assert!(shake(&slow, &one_euro) < shake(&slow, &exponential) * 1.5);
+
+    // ...but the One Euro filter lags much less on fast ones
+    let fast = shaky_line(60, 3.0, 1.0);
+    assert!(lag(&fast, &one_euro) < lag(&fast, &exponential) * 0.25);
+
+    // A Kalman filter follows steady movement without falling behind
+    let kalman = options(SmootherType::Kalman {
+        process_noise: 0.01,
+        measurement_noise: 1.0,
+    });
+    assert!(lag(&shaky_line(120, 3.0, 0.0), &kalman) < 1.0);
+}
+
+#[test]
+fn test_lazy_brush_ignores_small_movements() {
+    let options = options(SmootherType::LazyBrush { radius: 5.0 });
+
+    // The pen never gets far enough from the brush to pull it
+    let still = shaky_line(30, 0.0, 2.0);
+    assert_eq!(get_stroke_points(&still, &options).len(), 1);
+
+    // Once it does, the brush trails the pen by the string's length
+    let moving = shaky_line(30, 1.0, 0.0);
+    assert!((lag(&moving, &options) - 5.0).abs() < 1e-9);
+}
+
+/// Snap every point to a grid of 10 units.
+#[derive(Debug, Clone)]
+struct GridSmoother;
+
+impl Smoother for GridSmoother {
+    fn start(&mut self, _point: [f64; 2], _timestamp: Option<f64>) {}
+
+    fn smooth(&mut self, point: [f64; 2], _timestamp: Option<f64>) -> [f64; 2] {
+        point.map(|n| (n / 10.0).round() * 10.0)
+    }
+
+    fn clone_box(&self) -> Box<dyn Smoother> {
+        Box::new(self.clone())
+    }
+}
+
+#[test]
+fn test_custom_smoother_and_builder() {
+    let options = options(SmootherType::Custom(|| Box::new(GridSmoother)));
+    let points = shaky_line(40, 0.5, 2.0);
+
+    let stroke_points = get_stroke_points(&points, &options);
+    for point in &stroke_points[1..] {
+        assert_eq!(point.point.map(|n| n % 10.0), [0.0, 0.0]);
+    }
+
+    // Building the stroke point by point gives the same outline
+    let options = StrokeOptions {
+        smoother: Some(SmootherType::OneEuro {
+            min_cutoff: 1.0,
+            beta: 0.01,
+        }),
+        ..options
+    };
+    let mut builder = StrokeBuilder::new(options.clone());
+    for point in &points {
+        builder.push(point.clone());
+    }
+    builder.finish();
+    let last = StrokeOptions {
+        last: Some(true),
+        ..options
+    };
+    assert_eq!(builder.outline(), get_stroke(&points, &last));
+}
@@ -0,0 +1,110 @@
+use freedraw::{get_stroke, InputPoint, StrokeBuilder, StrokeOptions, TaperOptions, TaperType};
+use serde_json::Value;
+use std::fs::File;
+use std::io::Read;
+
+fn load_test_data(filename: &str) -> serde_json::Value {
+    let mut file = File::open(format!("tests/{}", filename)).expect("Could not open file");
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).expect("Could not read file");
+    serde_json::from_str(&contents).expect("Could not parse JSON")
+}
+
+fn convert_json_to_input_points(points_json: &[Value]) -> Vec<InputPoint> {
+    points_json
+        .iter()
+        .map(|point| {
+            let coords = point.as_array().unwrap();
+            InputPoint::Array(
+                [coords[0].as_f64().unwrap(), coords[1].as_f64().unwrap()],
+                coords.get(2).and_then(|p| p.as_f64()),
+            )
+        })
+        .collect()
+}
+
+fn assert_matches_get_stroke(input_points: &[InputPoint], options: &StrokeOptions) {
+    let mut builder = StrokeBuilder::new(options.clone());
+    let mut left: Vec<[f64; 2]> = Vec::new();
+
+    for (i, point) in input_points.iter().enumerate() {
+        let delta = builder.push(point.clone());
+
+        // Applying the delta should give the builder's left side
+        left.truncate(delta.left_start);
+        left.extend(delta.left);
+        assert_eq!(left, builder.left());
+
+        assert_eq!(builder.outline(), get_stroke(&input_points[..=i], options));
+    }
+
+    builder.finish();
+
+    let complete = StrokeOptions {
+        last: Some(true),
+        ..options.clone()
+    };
+    assert_eq!(builder.outline(), get_stroke(input_points, &complete));
+}
+
+#[test]
+fn test_stroke_builder_matches_get_stroke() {
+    let sample = load_test_data("sample.json");
+    let input_points = convert_json_to_input_points(sample.as_array().unwrap());
+
+    assert_matches_get_stroke(&input_points, &StrokeOptions::default());
+    assert_matches_get_stroke(
+        &input_points,
+        &StrokeOptions {
+            simulate_pressure: Some(false),
+            ..Default::default()
+        },
+    );
+    assert_matches_get_stroke(
+        &input_points,
+        &StrokeOptions {
+            backfill: Some(true),
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_stroke_builder_with_tapers() {
+    let corners = load_test_data("corners.json");
+    let input_points = convert_json_to_input_points(corners["corners"].as_array().unwrap());
+
+    for taper in [TaperType::Number(40.0), TaperType::Bool(true)] {
+        let options = StrokeOptions {
+            start: Some(TaperOptions {
+                taper: Some(taper.clone()),
+                ..Default::default()
+            }),
+            end: Some(TaperOptions {
+                taper: Some(taper),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_matches_get_stroke(&input_points, &options);
+    }
+}
+
+#[test]
+fn test_stroke_builder_only_updates_the_end() {
+    let flash = load_test_data("flash.json");
+    let input_points = convert_json_to_input_points(flash.as_array().unwrap());
+
+    let mut builder = StrokeBuilder::new(StrokeOptions::default());
+    let mut last_delta = None;
+    for point in &input_points {
+        last_delta = Some(builder.push(point.clone()));
+    }
+
+    // Once the stroke is long enough, only the end of it is rebuilt
+    let delta = last_delta.unwrap();
+    assert!(delta.left_start > 0);
+    assert!(delta.right_start > 0);
+    assert!(delta.left.len() < builder.left().len());
+}
@@ -0,0 +1,102 @@
+use freedraw::{get_stroke, Bounds, Containment, InputPoint, StrokeCollection, StrokeOptions};
+
+/// The outline of a straight line from one point to another.
+fn line(from: [f64; 2], to: [f64; 2]) -> Vec<[f64; 2]> {
+    let points: Vec<InputPoint> = (0..=20)
+        .map(|i| {
+            let t = i as f64 / 20.0;
+            let point = [from[0] + (to[0] - from[0]) * t, from[1] + (to[1] - from[1]) * t];
+            InputPoint::Array(point, Some(0.5))
+        })
+        .collect();
+
+    let options = StrokeOptions {
+        size: Some(8.0),
+        last: Some(true),
+        ..Default::default()
+    };
+    get_stroke(&points, &options)
+}
+
+fn bounds(min: [f64; 2], max: [f64; 2]) -> Bounds {
+    Bounds { min, max }
+}
+
+#[test]
+fn test_query_and_remove() {
+    let mut strokes = StrokeCollection::with_cell_size(100.0);
+    let a = strokes.insert(line([20.0, 20.0], [80.0, 20.0]));
+    let b = strokes.insert(line([500.0, 500.0], [650.0, 520.0]));
+    // Much bigger than the cells, so it's kept out of the grid
+    let c = strokes.insert(line([-5000.0, 0.0], [5000.0, 3000.0]));
+    let empty = strokes.insert(Vec::new());
+    assert_eq!(strokes.len(), 4);
+    assert_eq!(strokes.bounds(empty), None);
+
+    assert_eq!(strokes.query_viewport(&bounds([0.0, 0.0], [100.0, 100.0])), vec![a, c]);
+    assert_eq!(strokes.query_viewport(&bounds([600.0, 400.0], [700.0, 600.0])), vec![b, c]);
+    assert_eq!(strokes.query_viewport(&bounds([-9e9, -9e9], [9e9, 9e9])), vec![a, b, c]);
+
+    let outline = strokes.remove(a).unwrap();
+    assert_eq!(outline, line([20.0, 20.0], [80.0, 20.0]));
+    assert!(strokes.remove(a).is_none());
+    assert!(strokes.get(a).is_none());
+    assert_eq!(strokes.query_viewport(&bounds([0.0, 0.0], [100.0, 100.0])), vec![c]);
+
+    strokes.remove(c);
+    assert!(strokes.query_viewport(&bounds([0.0, 0.0], [100.0, 100.0])).is_empty());
+
+    // Ids aren't reused
+    let d = strokes.insert(line([20.0, 20.0], [80.0, 20.0]));
+    assert!(d > empty);
+    let ids: Vec<_> = strokes.iter().map(|(id, _)| id).collect();
+    assert_eq!(ids, vec![b, empty, d]);
+}
+
+#[test]
+fn test_nearest_and_select_box() {
+    let mut strokes = StrokeCollection::new();
+    let a = strokes.insert(line([0.0, 0.0], [100.0, 0.0]));
+    let b = strokes.insert(line([0.0, 30.0], [100.0, 30.0]));
+    // A diagonal whose bounds cover the box below without the stroke reaching it
+    let c = strokes.insert(line([200.0, 0.0], [400.0, 200.0]));
+
+    let (id, distance) = strokes.nearest([50.0, 10.0], 20.0).unwrap();
+    assert_eq!(id, a);
+    assert!(distance > 0.0 && distance < 10.0);
+    let (id, distance) = strokes.nearest([50.0, 30.0], 20.0).unwrap();
+    assert_eq!(id, b);
+    assert!(distance < 0.0);
+    assert!(strokes.nearest([50.0, 100.0], 20.0).is_none());
+
+    // The point is within the diagonal's bounds but far from the stroke
+    assert!(strokes.nearest([380.0, 20.0], 20.0).is_none());
+
+    let selection = strokes.select_box(&bounds([-10.0, -10.0], [110.0, 15.0]));
+    assert_eq!(selection, vec![(a, Containment::Inside)]);
+
+    let selection = strokes.select_box(&bounds([50.0, -10.0], [150.0, 40.0]));
+    assert_eq!(selection, vec![(a, Containment::Partial), (b, Containment::Partial)]);
+
+    assert!(strokes.select_box(&bounds([350.0, 10.0], [390.0, 50.0])).is_empty());
+
+    // A box inside a stroke touches it
+    let selection = strokes.select_box(&bounds([300.0, 99.0], [301.0, 101.0]));
+    assert_eq!(selection, vec![(c, Containment::Partial)]);
+}
+
+#[test]
+fn test_nearest_inside_overlapping_strokes() {
+    let mut strokes = StrokeCollection::new();
+    // The point is just inside the first stroke, and in the middle of the
+    // second, whose bounds are no nearer than the first's
+    let a = strokes.insert(line([0.0, 0.0], [100.0, 0.0]));
+    let b = strokes.insert(line([50.0, -50.0], [50.0, 50.0]));
+
+    let (id, distance) = strokes.nearest([20.0, 2.0], 20.0).unwrap();
+    assert_eq!(id, a);
+    assert!(distance < 0.0);
+    let (id, deeper) = strokes.nearest([50.0, 2.0], 20.0).unwrap();
+    assert_eq!(id, b);
+    assert!(deeper < distance);
+}
@@ -0,0 +1,147 @@
+use freedraw::{get_stroke, Bounds, FillRule, InputPoint, StrokeOptions, SvgDocument, SvgStroke};
+use std::fs::File;
+use std::io::Read;
+
+fn load_sample_points() -> Vec<InputPoint> {
+    let mut file = File::open("tests/sample.json").expect("Could not open sample.json");
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).expect("Could not read file");
+    let points: Vec<Vec<f64>> = serde_json::from_str(&contents).expect("Could not parse JSON");
+
+    points
+        .iter()
+        .map(|p| InputPoint::Array([p[0], p[1]], p.get(2).copied()))
+        .collect()
+}
+
+fn view_box(svg: &str) -> Vec<f64> {
+    let start = svg.find("viewBox=\"").expect("No viewBox") + "viewBox=\"".len();
+    let end = start + svg[start..].find('"').unwrap();
+    svg[start..end].split(' ').map(|n| n.parse().unwrap()).collect()
+}
+
+#[test]
+fn test_svg_document_fits_outlines() {
+    let points = load_sample_points();
+    let outline = get_stroke(
+        &points,
+        &StrokeOptions {
+            size: Some(40.0),
+            ..Default::default()
+        },
+    );
+
+    let mut document = SvgDocument::new();
+    document.add_stroke(SvgStroke::new(outline.clone()));
+
+    // The bounds come from the outline, which is wider than the input points
+    let bounds = document.bounds().unwrap();
+    assert_eq!(Some(bounds), Bounds::from_points(&outline));
+
+    let inputs: Vec<[f64; 2]> = points
+        .iter()
+        .map(|point| match point {
+            InputPoint::Array(point, _) => *point,
+            InputPoint::Struct { x, y, .. } => [*x, *y],
+        })
+        .collect();
+    let input_bounds = Bounds::from_points(&inputs).unwrap();
+    assert!(bounds.width() > input_bounds.width() + 20.0);
+    assert!(bounds.height() > input_bounds.height() + 20.0);
+
+    // The view box fits every point of the written path
+    let svg = document.to_svg();
+    let [x, y, width, height] = view_box(&svg)[..] else {
+        panic!("Bad viewBox in {}", svg);
+    };
+    assert!(x <= bounds.min[0] && y <= bounds.min[1]);
+    assert!(x + width >= bounds.max[0] && y + height >= bounds.max[1]);
+    assert!(width - bounds.width() < 0.02 && height - bounds.height() < 0.02);
+
+    // Padding and lines drawn along the outline make it bigger
+    let mut document = SvgDocument {
+        padding: Some(5.0),
+        ..SvgDocument::new()
+    };
+    document.add_stroke(SvgStroke {
+        stroke: Some("red".to_string()),
+        stroke_width: Some(4.0),
+        ..SvgStroke::new(outline)
+    });
+    assert_eq!(document.bounds(), Some(bounds.expand(2.0)));
+    let padded = view_box(&document.to_svg());
+    assert!((padded[2] - (width + 14.0)).abs() < 0.02);
+
+    // The aspect ratio is only written when it's given
+    assert!(!svg.contains("preserveAspectRatio"));
+    document.preserve_aspect_ratio = Some("xMidYMid meet".to_string());
+    assert!(document.to_svg().contains(" preserveAspectRatio=\"xMidYMid meet\""));
+}
+
+#[test]
+fn test_svg_document_layers() {
+    let square = vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]];
+    let far = square.iter().map(|p| [p[0] + 100.0, p[1] + 100.0]).collect();
+
+    let mut document = SvgDocument {
+        fill_rule: Some(FillRule::EvenOdd),
+        ..SvgDocument::new()
+    };
+    document.add_stroke(SvgStroke {
+        fill: Some("#ff0000".to_string()),
+        opacity: Some(0.5),
+        ..SvgStroke::new(square.clone())
+    });
+
+    let layer = document.add_layer("ink & \"notes\"");
+    layer.opacity = Some(0.25);
+    layer.add_stroke(SvgStroke {
+        fill_rule: Some(FillRule::NonZero),
+        ..SvgStroke::new(square.clone())
+    });
+    layer.add_group("empty");
+
+    // Hidden groups are written, but don't count towards the bounds
+    let hidden = document.add_layer("hidden");
+    hidden.visible = Some(false);
+    hidden.add_stroke(SvgStroke::new(far));
+
+    assert_eq!(
+        document.to_svg(),
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 10 10\" width=\"10\" height=\"10\" fill-rule=\"evenodd\">\n",
+            "  <path d=\"M0.00,0.00 Q10.00,0.00 10.00,5.00 T5.00,10.00 0.00,5.00 Z\" fill=\"#ff0000\" opacity=\"0.5\"/>\n",
+            "  <g id=\"ink &amp; &quot;notes&quot;\" opacity=\"0.25\">\n",
+            "    <path d=\"M0.00,0.00 Q10.00,0.00 10.00,5.00 T5.00,10.00 0.00,5.00 Z\" fill-rule=\"nonzero\"/>\n",
+            "    <g id=\"empty\"/>\n",
+            "  </g>\n",
+            "  <g id=\"hidden\" display=\"none\">\n",
+            "    <path d=\"M100.00,100.00 Q110.00,100.00 110.00,105.00 T105.00,110.00 100.00,105.00 Z\"/>\n",
+            "  </g>\n",
+            "</svg>\n",
+        )
+    );
+}
+
+#[test]
+fn test_svg_document_rings() {
+    let outer = vec![[0.0, 0.0], [20.0, 0.0], [20.0, 20.0], [0.0, 20.0], [0.0, 0.0]];
+    let inner = vec![[5.0, 5.0], [5.0, 15.0], [15.0, 15.0], [15.0, 5.0], [5.0, 5.0]];
+
+    let mut document = SvgDocument::new();
+    document.add_stroke(SvgStroke::from_rings(vec![outer, inner]));
+    let svg = document.to_svg();
+
+    // Both rings go into one path
+    assert_eq!(svg.matches("<path").count(), 1);
+    assert_eq!(svg.matches('M').count(), 2);
+
+    // Nothing to draw
+    let mut document = SvgDocument::new();
+    document.add_stroke(SvgStroke::new(vec![[0.0, 0.0]]));
+    assert_eq!(document.bounds(), None);
+    let svg = document.to_svg();
+    assert!(!svg.contains("<path"));
+    assert_eq!(view_box(&svg), vec![0.0, 0.0, 0.0, 0.0]);
+}
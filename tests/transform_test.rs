@@ -0,0 +1,134 @@
+use freedraw::{
+    get_stroke, get_stroke_outline_points, get_stroke_points, InputPoint, Nib, PenOrientation,
+    StrokeOptions, TaperOptions, TaperType, Transform,
+};
+use std::f64::consts::FRAC_PI_2;
+use std::fs::File;
+use std::io::Read;
+
+fn load_sample_points() -> Vec<InputPoint> {
+    let mut file = File::open("tests/sample.json").expect("Could not open sample.json");
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).expect("Could not read file");
+    let points: Vec<Vec<f64>> = serde_json::from_str(&contents).expect("Could not parse JSON");
+
+    points
+        .iter()
+        .map(|p| InputPoint::Array([p[0], p[1]], p.get(2).copied()))
+        .collect()
+}
+
+fn assert_close(a: &[[f64; 2]], b: &[[f64; 2]], tolerance: f64) {
+    assert_eq!(a.len(), b.len());
+    for (a, b) in a.iter().zip(b) {
+        let is_close = (a[0] - b[0]).abs() < tolerance && (a[1] - b[1]).abs() < tolerance;
+        assert!(is_close, "{:?} {:?}", a, b);
+    }
+}
+
+#[test]
+fn test_transformed_stroke_matches_transformed_outline() {
+    let points = load_sample_points();
+    let options = StrokeOptions {
+        size: Some(12.0),
+        start: Some(TaperOptions {
+            taper: Some(TaperType::Number(40.0)),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let transform = Transform::rotate_around(0.7, [100.0, 100.0])
+        .then(Transform::scale(2.5))
+        .then(Transform::translate(30.0, -20.0));
+    assert!((transform.width_scale() - 2.5).abs() < 1e-12);
+
+    // Drawing the moved points with the scaled options gives the same shape as
+    // moving the outline
+    let moved = get_stroke(
+        &transform.transform_points(&points),
+        &transform.transform_options(&options),
+    );
+    let outline = transform.transform_outline(&get_stroke(&points, &options));
+    assert_close(&moved, &outline, 1e-6);
+
+    let scaled = transform.transform_options(&options);
+    assert_eq!(scaled.size, Some(30.0));
+    let taper = scaled.start.as_ref().and_then(|start| start.taper.clone()).unwrap();
+    assert!(matches!(taper, TaperType::Number(n) if n == 100.0));
+
+    // The same goes for stroke points
+    let stroke_points = get_stroke_points(&points, &options);
+    let moved_points = transform.transform_stroke_points(&stroke_points);
+    let expected = get_stroke_points(&transform.transform_points(&points), &scaled);
+    assert_eq!(moved_points.len(), expected.len());
+    for (a, b) in moved_points.iter().zip(&expected) {
+        assert!((a.running_length - b.running_length).abs() < 1e-6);
+        assert!((a.distance - b.distance).abs() < 1e-6);
+        assert!((a.vector[0] - b.vector[0]).abs() < 1e-6);
+    }
+    assert_close(
+        &get_stroke_outline_points(&moved_points, &scaled),
+        &outline,
+        1e-6,
+    );
+}
+
+#[test]
+fn test_transformed_default_options() {
+    let points = load_sample_points();
+    let options = StrokeOptions::default();
+    let transform = Transform::scale(2.0);
+
+    // The default size is scaled too
+    let scaled = transform.transform_options(&options);
+    assert_eq!(scaled.size, Some(32.0));
+
+    let moved = get_stroke(&transform.transform_points(&points), &scaled);
+    let outline = transform.transform_outline(&get_stroke(&points, &options));
+    assert_close(&moved, &outline, 1e-6);
+}
+
+#[test]
+fn test_non_uniform_scale() {
+    let transform = Transform::scale_xy(4.0, 1.0);
+    assert_eq!(transform.width_scale(), 2.0);
+    assert_eq!(transform.apply([1.0, 1.0]), [4.0, 1.0]);
+
+    let options = StrokeOptions {
+        size: Some(8.0),
+        nib: Some(Nib::Fixed {
+            angle: FRAC_PI_2 / 2.0,
+            aspect: 0.5,
+        }),
+        ..Default::default()
+    };
+    let scaled = transform.transform_options(&options);
+    assert_eq!(scaled.size, Some(16.0));
+
+    // A fixed nib at 45 degrees is turned toward the stretched axis
+    let Some(Nib::Fixed { angle, aspect }) = scaled.nib else {
+        panic!("the nib should still be fixed");
+    };
+    assert!((angle - 0.25f64.atan()).abs() < 1e-12);
+    assert_eq!(aspect, 0.5);
+
+    // The pen's lean turns with the points, and how far it leans doesn't change
+    let point = InputPoint::Struct {
+        x: 1.0,
+        y: 2.0,
+        pressure: Some(0.5),
+        timestamp: Some(16.0),
+        orientation: Some(PenOrientation {
+            tilt_x: Some(30.0),
+            tilt_y: Some(0.0),
+            ..Default::default()
+        }),
+    };
+    let turned = Transform::rotate(FRAC_PI_2).transform_points(std::slice::from_ref(&point));
+    let orientation = turned[0].orientation().unwrap();
+    assert!((orientation.azimuth.unwrap() - FRAC_PI_2).abs() < 1e-12);
+    assert_eq!(orientation.tilt_angle(), point.orientation().unwrap().tilt_angle());
+    assert_eq!(turned[0].timestamp(), Some(16.0));
+    let [x, y] = turned[0].point();
+    assert!((x + 2.0).abs() < 1e-12 && (y - 1.0).abs() < 1e-12);
+}
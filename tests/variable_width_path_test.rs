@@ -0,0 +1,125 @@
+use freedraw::{
+    get_stroke, get_stroke_points, get_stroke_radius, Bounds, InputPoint, PathSink, StrokeOptions,
+    VariableWidthPath,
+};
+use std::fs::File;
+use std::io::Read;
+
+fn load_sample_points() -> Vec<InputPoint> {
+    let mut file = File::open("tests/sample.json").expect("Could not open sample.json");
+    let mut contents = String::new();
+    file.read_to_string(&mut contents).expect("Could not read file");
+    let points: Vec<Vec<f64>> = serde_json::from_str(&contents).expect("Could not parse JSON");
+
+    points
+        .iter()
+        .map(|p| InputPoint::Array([p[0], p[1]], p.get(2).copied()))
+        .collect()
+}
+
+/// Collects points along the drawn centerline.
+#[derive(Default)]
+struct Sampler(Vec<[f64; 2]>);
+
+impl PathSink for Sampler {
+    fn move_to(&mut self, point: [f64; 2]) {
+        self.0.push(point);
+    }
+
+    fn line_to(&mut self, point: [f64; 2]) {
+        self.0.push(point);
+    }
+
+    fn quad_to(&mut self, _control: [f64; 2], point: [f64; 2]) {
+        self.0.push(point);
+    }
+
+    fn cubic_to(&mut self, c1: [f64; 2], c2: [f64; 2], point: [f64; 2]) {
+        let from = *self.0.last().unwrap();
+        for i in 1..=200 {
+            let t = i as f64 / 200.0;
+            let s = 1.0 - t;
+            let point = [0, 1].map(|k| {
+                s * s * s * from[k]
+                    + 3.0 * s * s * t * c1[k]
+                    + 3.0 * s * t * t * c2[k]
+                    + t * t * t * point[k]
+            });
+            self.0.push(point);
+        }
+    }
+
+    fn close(&mut self) {}
+}
+
+#[test]
+fn test_fit_variable_width_path() {
+    let options = StrokeOptions {
+        size: Some(16.0),
+        thinning: Some(0.6),
+        simulate_pressure: Some(false),
+        ..Default::default()
+    };
+    let stroke_points = get_stroke_points(&load_sample_points(), &options);
+    let path = VariableWidthPath::fit(&stroke_points, &options, 0.5);
+
+    // Far fewer knots than points, with a curve between each pair
+    assert!(path.knots.len() > 1);
+    assert!(path.knots.len() * 4 < stroke_points.len());
+    assert_eq!(path.controls.len(), path.knots.len() - 1);
+
+    // The ends of the path are the ends of the stroke
+    assert_eq!(path.knots[0].point, stroke_points[0].point);
+    assert_eq!(path.knots.last().unwrap().point, stroke_points.last().unwrap().point);
+
+    // Each knot is a stroke point, with the radius for its pressure
+    for knot in &path.knots {
+        let point = stroke_points.iter().find(|p| p.point == knot.point).unwrap();
+        let radius = get_stroke_radius(16.0, 0.6, point.pressure, None);
+        assert!((knot.radius - radius).abs() < 1e-9);
+    }
+
+    // Every stroke point is close to the path
+    let mut sampler = Sampler::default();
+    path.draw(&mut sampler);
+    for point in &stroke_points {
+        let distance = sampler
+            .0
+            .iter()
+            .map(|p| ((p[0] - point.point[0]).powi(2) + (p[1] - point.point[1]).powi(2)).sqrt())
+            .fold(f64::INFINITY, f64::min);
+        assert!(distance < 0.6, "{:?} is {} from the path", point.point, distance);
+    }
+
+    // Turned back into an outline, it covers about the same area as the stroke
+    let outline = path.to_outline(&options);
+    let original = get_stroke(&load_sample_points(), &options);
+    let bounds = Bounds::from_points(&outline).unwrap();
+    let original_bounds = Bounds::from_points(&original).unwrap();
+    for k in 0..2 {
+        assert!((bounds.min[k] - original_bounds.min[k]).abs() < 1.0);
+        assert!((bounds.max[k] - original_bounds.max[k]).abs() < 1.0);
+    }
+}
+
+#[test]
+fn test_fit_straight_line() {
+    let points: Vec<InputPoint> = (0..20)
+        .map(|i| InputPoint::Array([i as f64 * 5.0, 10.0], Some(0.5)))
+        .collect();
+    let options = StrokeOptions {
+        simulate_pressure: Some(false),
+        ..Default::default()
+    };
+    let stroke_points = get_stroke_points(&points, &options);
+
+    // A straight line with an even width is a single curve
+    let path = VariableWidthPath::fit(&stroke_points, &options, 0.1);
+    assert_eq!(path.knots.len(), 2);
+    assert!(path.controls[0].iter().all(|control| (control[1] - 10.0).abs() < 1e-9));
+
+    // Nothing to fit
+    let path = VariableWidthPath::fit(&[], &options, 0.1);
+    assert!(path.knots.is_empty() && path.controls.is_empty());
+    assert!(path.to_outline(&options).is_empty());
+}